@@ -6,15 +6,45 @@ Paradigm(s): Procedural, Functional, Data-Oriented
 
 use std::io::{self, Write};
 use std::error::Error;
-use std::fs::File;
 use std::path::{Path, PathBuf};
-use std::cmp::Ordering;
-use csv::{ReaderBuilder, Writer};
+use csv::Writer;
 use serde::{Deserialize, Serialize};
-use serde_json;
 use chrono::NaiveDate;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use num_format::{Locale, ToFormattedString};
+use config::{AnalysisConfig, DurationConfig, SortConfig, SortKey};
+use money::MoneyCounts;
+use intern::{Interner, Name, NameKey};
+use status::ProjectStatus;
+
+mod bench;
+mod benford;
+mod bid_ratio;
+mod compare;
+mod config;
+mod dates;
+mod dedup;
+mod diff;
+mod distributions;
+mod drill;
+mod geo;
+mod growth;
+mod ingest;
+mod intern;
+mod islands;
+mod money;
+mod numeric;
+mod ordering;
+mod outliers;
+mod schema;
+mod server;
+mod snapshot;
+mod splitting;
+mod statistics;
+mod status;
+mod timecompare;
+mod timeseries;
+mod validation;
 
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
@@ -45,37 +75,99 @@ struct RawFloodControlProject {
     contractor: String,
     #[serde(rename = "StartDate")]
     start_date: String,
-    #[serde(rename = "ProjectLatitude")]
+    //the location columns are optional, see schema.rs
+    #[serde(rename = "ProjectLatitude", default)]
     project_latitude: String,
-    #[serde(rename = "ProjectLongitude")]
+    #[serde(rename = "ProjectLongitude", default)]
     project_longitude: String,
-    #[serde(rename = "ProvincialCapital")]
+    #[serde(rename = "ProvincialCapital", default)]
     provincial_capital: String,
-    #[serde(rename = "ProvincialCapitalLatitude")]
+    #[serde(rename = "ProvincialCapitalLatitude", default)]
     provincial_capital_latitude: String,
-    #[serde(rename = "ProvincialCapitalLongitude")]
+    #[serde(rename = "ProvincialCapitalLongitude", default)]
     provincial_capital_longitude: String,
+    //optional planned-duration columns; most extracts have neither
+    #[serde(rename = "ContractDuration", default)]
+    contract_duration: String,
+    #[serde(rename = "TargetCompletionDate", default)]
+    target_completion_date: String,
+}
+
+#[cfg(test)]
+impl RawFloodControlProject {
+    //one raw CSV row: a completed 2022 flood mitigation project, with `fields` (column, value) replaced or added
+    fn sample(fields: &[(&str, &str)]) -> RawFloodControlProject {
+        let mut row: Vec<(&str, &str)> = vec![
+            ("MainIsland", "Luzon"), ("Region", "Region I"), ("Province", "Province"), ("ProjectId", "P1"),
+            ("ProjectName", "Construction of Flood Wall"), ("TypeOfWork", "Construction of Flood Mitigation Structure"),
+            ("FundingYear", "2022"), ("ContractId", "C1"), ("ApprovedBudgetForContract", "1000000"),
+            ("ContractCost", "950000"), ("ActualCompletionDate", "2022-11-06"), ("Contractor", "Builder A"),
+            ("StartDate", "2022-03-01"),
+        ];
+        for &(column, value) in fields {
+            match row.iter_mut().find(|(name, _)| *name == column) {
+                Some(field) => field.1 = value,
+                None => row.push((column, value)),
+            }
+        }
+        let headers = csv::StringRecord::from(row.iter().map(|(name, _)| *name).collect::<Vec<_>>());
+        let record = csv::StringRecord::from(row.iter().map(|(_, value)| *value).collect::<Vec<_>>());
+        record.deserialize(Some(&headers)).unwrap()
+    }
 }
 
 //proper data types
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 struct FloodControlProject {
-    main_island: String,
-    region: String,
-    province: String,
+    //categorical fields are interned, see intern.rs
+    main_island: Name,
+    region: Name,
+    province: Name,
     project_id: String,
     project_name: String,
-    type_of_work: String,
+    type_of_work: Name,
     funding_year: u32,
     contract_id: String,
     approved_budget: f64,
     contract_cost: f64,
-    actual_completion_date: NaiveDate, 
+    actual_completion_date: Option<NaiveDate>, //None while the project is ongoing
     start_date: NaiveDate,             
-    contractor: String,
+    contractor: Name,
     cost_savings: f64,
-    completion_delay_days: i64,
+    duration_days: Option<i64>, //start to actual completion; None for ongoing projects
+    planned_duration_days: i64, //from the contract columns, else the standard for the type of work
+    delay_days: Option<i64>, //duration minus planned duration; negative when finished early
+    status: ProjectStatus, //derived from the two dates; decides which metrics include the project
+}
+
+#[cfg(test)]
+impl FloodControlProject {
+    //a completed 2022 project taking its planned 100 days, for unit tests to adjust
+    fn sample(project_id: &str, region: &str, contractor: &str) -> FloodControlProject {
+        let start_date = NaiveDate::from_ymd_opt(2022, 3, 1).unwrap();
+        let actual_completion_date = start_date + chrono::Duration::days(100);
+        FloodControlProject {
+            main_island: "Luzon".into(),
+            region: region.into(),
+            province: "Province".into(),
+            project_id: project_id.to_string(),
+            project_name: format!("Construction of Flood Wall {}", project_id),
+            type_of_work: "Construction of Flood Mitigation Structure".into(),
+            funding_year: 2022,
+            contract_id: format!("C-{}", project_id),
+            approved_budget: 1_000_000.0,
+            contract_cost: 950_000.0,
+            actual_completion_date: Some(actual_completion_date),
+            start_date,
+            contractor: contractor.into(),
+            cost_savings: 50_000.0,
+            duration_days: Some(100),
+            planned_duration_days: 100,
+            delay_days: Some(0),
+            status: ProjectStatus::classify(start_date, Some(actual_completion_date)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -84,9 +176,11 @@ struct RegionalStats {
     main_island: String,
     total_budget: f64,
     median_savings: f64,
+    avg_duration: f64,
     avg_delay: f64,
     high_delay_pct: f64,
-    efficiency_score: f64,
+    #[serde(serialize_with = "numeric::serialize_defined")]
+    efficiency_score: Option<f64>, //None when savings per day of delay cannot be ranked
 }
 
 #[allow(dead_code)]
@@ -95,9 +189,11 @@ struct ContractorStats {
     contractor: String,
     project_count: u32,
     total_contract_cost: f64,
+    avg_duration_days: f64,
     avg_completion_delay_days: f64,
     total_cost_savings: f64,
-    reliability_index: f64,
+    #[serde(serialize_with = "numeric::serialize_defined")]
+    reliability_index: Option<f64>, //None without a contract cost to divide savings by
     risk_flag: String,
 }
 
@@ -109,21 +205,48 @@ struct CostOverrunStats {
     total_projects: u32,
     avg_cost_savings: f64,
     overrun_rate: f64,
-    yoy_change: f64,
+    avg_duration_days: f64,
+    avg_delay_days: f64,
+    yoy_change: Option<f64>, //None when no earlier year exists or its average is zero
+    yoy_base_year: Option<u32>,
+    cagr: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
 struct Summary {
     total_projects: usize,
+    ongoing_projects: usize, //no completion date; not part of the average delay
+    invalid_timeline_projects: usize, //completion before start or implausibly long; not part of the average delay
     total_contractors: usize,
     total_provinces: usize,
     total_regions: usize,
+    global_average_duration_days: f64,
     global_average_delay_days: f64,
     total_savings: f64,
     total_budget: f64,
     date_range: String,
 }
 
+//funding years the analysis covers; rows outside it are neither validated nor reported
+const FUNDING_YEARS: std::ops::RangeInclusive<u32> = 2021..=2023;
+//a completed project more than a month past its planned duration counts as high delay
+const HIGH_DELAY_DAYS: i64 = 30;
+const MIN_CONTRACTOR_PROJECTS: u32 = 5;
+const TOP_CONTRACTORS: usize = 15;
+
+//reports outside the three required ones: (command name, menu label)
+const ADDITIONAL_REPORTS: &[(&str, &str)] = &[
+    ("distributions", "Distribution Statistics (Region / Type of Work)"),
+    ("outliers", "Outlier and Anomaly Detection"),
+    ("bid-ratio", "Bid-to-Budget Ratio Analysis"),
+    ("benford", "Benford's Law Conformity Test"),
+    ("splitting", "Contract Splitting Detection"),
+    ("timeseries", "Monthly / Quarterly Time Series"),
+    ("status", "Project Status by Region / Contractor"),
+    ("islands", "Main Island Rollup / Region Island Check"),
+    ("growth", "Year-over-Year Growth of Report Metrics"),
+];
+
 fn print_menu() {
     println!("\n==============================================");
     println!("Flood Control Project Data Analysis Menu");
@@ -131,7 +254,31 @@ fn print_menu() {
     println!("Select an option:");
     println!("[1]  Load Data File");
     println!("[2]  Generate Reports");
-    println!("[3]  Exit");
+    println!("[3]  Additional Reports");
+    println!("[4]  Exit");
+}
+
+fn print_additional_reports_menu() {
+    println!("\nAdditional Reports:");
+    for (index, (_, label)) in ADDITIONAL_REPORTS.iter().enumerate() {
+        println!("[{}]  {}", index + 1, label);
+    }
+    println!("[0]  Back");
+}
+
+fn run_additional_report(name: &str, projects: &[FloodControlProject], config: &AnalysisConfig) -> Result<(), Box<dyn Error>> {
+    match name {
+        "distributions" => distributions::distributions_report(projects),
+        "outliers" => outliers::outlier_report(projects),
+        "bid-ratio" => bid_ratio::bid_ratio_report(projects),
+        "benford" => benford::benford_report(projects),
+        "splitting" => splitting::splitting_report(projects, &config.splitting),
+        "timeseries" => timeseries::timeseries_report(projects),
+        "status" => status::status_report(projects),
+        "islands" => islands::islands_report(projects),
+        "growth" => growth::growth_report(projects, &config.growth),
+        other => Err(format!("Unknown report: {}", other).into()),
+    }
 }
 
 fn input_integer() -> u32 { //function to ask user input number
@@ -146,114 +293,129 @@ fn input_integer() -> u32 { //function to ask user input number
     }
 }
 
-fn parse_budget(budget_str: &str) -> Option<f64> {
-    budget_str.trim().parse::<f64>().ok()
+fn parse_budget(budget_str: &str, config: &AnalysisConfig, money_counts: &mut MoneyCounts) -> Option<f64> {
+    let parsed = money::parse_money(budget_str, config.parsing.money_mode);
+    money_counts.record(&parsed);
+    parsed.amount()
+}
+
+//planned duration in days: target completion date, then contract duration ("120", "120 CD"),
+//then the configured standard for the type of work
+fn planned_duration(raw: &RawFloodControlProject, start: NaiveDate, config: &DurationConfig) -> i64 {
+    if let Some(target) = dates::parse_date(&raw.target_completion_date) {
+        return (target - start).num_days();
+    }
+    let digits: String = raw.contract_duration.trim().chars().take_while(|c| c.is_ascii_digit()).collect();
+    match digits.parse::<i64>() {
+        Ok(days) if days > 0 => days,
+        _ => config.standard_for(&raw.type_of_work),
+    }
 }
 
-fn clean_project(raw: RawFloodControlProject) 
+fn clean_project(raw: RawFloodControlProject, config: &AnalysisConfig, money_counts: &mut MoneyCounts, names: &mut Interner) 
     -> Option<FloodControlProject> {
     
     // filtering: funding year must be 2021-2023
     let year = match raw.funding_year {
-        Some(y) if y >= 2021 && y <= 2023 => y,
+        Some(y) if FUNDING_YEARS.contains(&y) => y,
         _ => return None,
     };
 
-    // filtering: must have valid dates (parse once, use for validation and calculation)
-    let start_date = NaiveDate::parse_from_str(&raw.start_date, "%Y-%m-%d").ok();
-    let mut end_date = NaiveDate::parse_from_str(&raw.actual_completion_date, "%Y-%m-%d").ok();
-    
-    if start_date.is_none() {
-        return None;
-    }
-
-    if end_date.is_none() {
-        end_date = start_date;
-    }
+    // the validation rules drop most unusable rows first; anything still unusable is dropped here
+    // filtering: must have a valid start date; a missing or unreadable completion date means ongoing
+    let start = dates::parse_date(&raw.start_date)?;
+    let end = dates::parse_date(&raw.actual_completion_date);
 
     // filtering: budget field must be float and > 0.0
-    let approved_budget = match parse_budget(&raw.approved_budget_for_contract) {
+    let approved_budget = match parse_budget(&raw.approved_budget_for_contract, config, money_counts) {
         Some(ab) if ab > 0.0 => ab,
         _ => return None,
     };
 
-    let contract_cost = match parse_budget(&raw.contract_cost) {
+    let contract_cost = match parse_budget(&raw.contract_cost, config, money_counts) {
         Some(cc) if cc > 0.0 => cc,
         _ => return None,
     };
 
-
-    let start = start_date.unwrap();
-    let end = end_date.unwrap();
+    let planned_duration_days = planned_duration(&raw, start, &config.duration);
+    let duration_days = end.map(|end| (end - start).num_days());
 
     Some(FloodControlProject {
-        main_island: raw.main_island,
-        region: raw.region,
-        province: raw.province,
+        main_island: names.intern(&raw.main_island),
+        region: names.intern(&raw.region),
+        province: names.intern(&raw.province),
         project_id: raw.project_id,
         project_name: raw.project_name,
-        type_of_work: raw.type_of_work,
+        type_of_work: names.intern(&raw.type_of_work),
         funding_year: year,
         contract_id: raw.contract_id,
         approved_budget,
         contract_cost,
         actual_completion_date: end,  
         start_date: start,           
-        contractor: raw.contractor,
+        contractor: names.intern(&raw.contractor),
         cost_savings: approved_budget - contract_cost,
-        completion_delay_days: (end - start).num_days(),
+        duration_days,
+        planned_duration_days,
+        delay_days: duration_days.map(|days| days - planned_duration_days),
+        status: ProjectStatus::classify(start, end),
     })
 }
 
-fn read_csv_file<P: AsRef<Path>>(filename: P) 
+fn read_csv_file<P: AsRef<Path>>(filename: P, config: &AnalysisConfig) 
+    -> Result<Vec<FloodControlProject>, Box<dyn Error>> {
+    load_projects(filename, config, true)
+}
+
+//the load-time reports (data quality, reference mismatches, duplicates) are always printed;
+//without `export_reports` their CSVs in data/ are left alone, e.g. for the extracts of a diff
+fn load_projects<P: AsRef<Path>>(filename: P, config: &AnalysisConfig, export_reports: bool)
     -> Result<Vec<FloodControlProject>, Box<dyn Error>> {
     let path = filename.as_ref();
-    let file = File::open(path)?;
-    let mut reader = ReaderBuilder::new().from_reader(file);
-    
-    let mut total_row_count: usize = 0;
-    let mut filtered_row_count: usize = 0;
-    let mut projects: Vec<FloodControlProject> = Vec::new();
-    
-    
-    for result in reader.deserialize::<RawFloodControlProject>() {
-        total_row_count += 1;
-        
-        if let Ok(raw_project) = result {
-        //if clean_project returns Some, add to projects
-            if let Some(fcp) = clean_project(raw_project) {
-                projects.push(fcp);
-                filtered_row_count += 1;
-            }
-        }
+    let source = std::fs::read(path)?;
+
+    if config.cache.enabled && let Some(projects) = snapshot::load(path, &source, config) {
+        return Ok(projects);
     }
 
-    println!("Processing data...({} rows loaded, {} filtered for 2021-2023)", total_row_count, filtered_row_count);
+    //rows are parsed and cleaned in parallel chunks, kept in file order
+    let mut projects: Vec<FloodControlProject> = Vec::new();
+    let counts = ingest::parse_projects(source.as_slice(), config, |chunk| projects.extend(chunk))?;
+    counts.report(config, export_reports)?;
     
+    let projects = dedup::deduplicate(projects, &config.dedup, export_reports)?;
+
+    if config.cache.enabled {
+        snapshot::save(path, &source, config, &projects);
+    }
     Ok(projects)
 }
 
-fn compute_median(v: &[f64]) -> f64 {
-    if v.is_empty() {
-        return 0.0;
-    }
+//cost savings as a percentage of the approved budget
+fn savings_ratio(project: &FloodControlProject) -> f64 {
+    (project.cost_savings / project.approved_budget) * 100.0
+}
 
-    let mut sorted = v.to_vec();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+//delay and duration as used by the report metrics: None unless the project is completed with a plausible timeline
+fn metric_delay_days(project: &FloodControlProject) -> Option<i64> {
+    project.delay_days.filter(|_| project.status.counts_toward_delay())
+}
 
-    let len = sorted.len();
-    if len % 2 == 1 {
-        sorted[len / 2]
-    } else {
-        (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
-    }
+fn metric_duration_days(project: &FloodControlProject) -> Option<i64> {
+    project.duration_days.filter(|_| project.status.counts_toward_delay())
+}
+
+//NaN savings are skipped, never compared
+fn compute_median(v: &[f64]) -> f64 {
+    statistics::median(v)
 }
 
-fn normalize_score(raw_score: f64, min_score: f64, max_score: f64) -> f64 {
+//undefined when the defined scores have no spread, e.g. a single region or all equal
+fn normalize_score(raw_score: Option<f64>, min_score: f64, max_score: f64) -> Option<f64> {
     if max_score > min_score {
-        ((raw_score - min_score) / (max_score - min_score)) * 100.0
+        raw_score.and_then(|raw| numeric::ratio(raw - min_score, max_score - min_score)).map(|share| share * 100.0)
     } else {
-        0.0
+        None
     }
 }
 
@@ -263,57 +425,101 @@ fn format_currency(amount: f64) -> String {
         ((amount.fract() * 100.0).abs() as i32))
 }
 
-fn aggregate_regional_stats(projects: &Vec<FloodControlProject>) -> Vec<RegionalStats> {
-    let mut results: Vec<RegionalStats> = Vec::new();
-    let mut regional_map: HashMap<String, (String, f64, Vec<f64>, i64, u32, u32)> = HashMap::new();
-    // region: (main_island, total_budget, cost_savings, total_completion_days, high_delay_count, project_count)
+type RegionalEntry<K> = (BTreeMap<K, u32>, f64, Vec<f64>, i64, i64, u32, u32, u32);
 
-    for project in projects {
-        let entry = regional_map.entry(project.region.clone()).or_insert((
-            project.main_island.clone(),
+//running totals are fed one project at a time, so the in-memory and streaming loaders share them
+#[derive(Default)]
+struct RegionalAccumulator<K: NameKey = Name> {
+    regional_map: HashMap<K, RegionalEntry<K>>,
+    // region: (projects per main_island, total_budget, cost_savings, total_duration_days, total_delay_days, high_delay_count, project_count, completed_count)
+}
+
+impl<K: NameKey> RegionalAccumulator<K> {
+    fn add(&mut self, project: &FloodControlProject) {
+        let entry = self.regional_map.entry(K::from_name(&project.region)).or_insert((
+            BTreeMap::new(),
             0.0,
             Vec::new(),
             0,
             0,
             0,
+            0,
+            0,
         )); //initate entry if not exists
 
+        *entry.0.entry(K::from_name(&project.main_island)).or_insert(0) += 1;
         entry.1 += project.approved_budget;
         entry.2.push(project.cost_savings);
-        entry.3 += project.completion_delay_days;
-        if project.completion_delay_days > 30 {
-            entry.4 += 1; 
-        }   
-        entry.5 += 1;
+        //duration and delay metrics only cover completed projects
+        if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+            entry.3 += duration_days;
+            entry.4 += delay_days;
+            if delay_days > HIGH_DELAY_DAYS {
+                entry.5 += 1; 
+            }
+            entry.7 += 1;
+        }
+        entry.6 += 1;
     }
 
+    fn finish(self) -> Vec<RegionalStats> {
+        compute_regional_stats(&self.regional_map)
+    }
+}
+
+//the island most of a region's projects list; ties go to the first alphabetically
+fn dominant_island<K: NameKey>(island_counts: &BTreeMap<K, u32>) -> &str {
+    island_counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(island, _)| &**island)
+        .unwrap_or_default()
+}
+
+fn aggregate_regional_stats(projects: &[FloodControlProject]) -> Vec<RegionalStats> {
+    let mut accumulator = RegionalAccumulator::<Name>::default();
+    for project in projects {
+        accumulator.add(project);
+    }
+    accumulator.finish()
+}
+
+fn compute_regional_stats<K: NameKey>(regional_map: &HashMap<K, RegionalEntry<K>>) -> Vec<RegionalStats> {
+    let mut results: Vec<RegionalStats> = Vec::new();
+
     //computing stats per region
-    for (region, (main_island, total_budget, cost_savings, total_completion_days, high_delay_count, project_count)) in &regional_map {
+    for (region, (island_counts, total_budget, cost_savings, total_duration_days, total_delay_days, high_delay_count, _, completed_count)) in regional_map {
         let median_savings = compute_median(cost_savings);
-        let avg_delay = if *project_count > 0 {
-            *total_completion_days as f64 / *project_count as f64
+        let avg_duration = if *completed_count > 0 {
+            *total_duration_days as f64 / *completed_count as f64
+        } else { 0.0 };
+        let avg_delay = if *completed_count > 0 {
+            *total_delay_days as f64 / *completed_count as f64
         } else { 0.0 };
-        let high_delay_pct = if *project_count > 0 {
-            (*high_delay_count as f64 / *project_count as f64) * 100.0
+        let high_delay_pct = if *completed_count > 0 {
+            (*high_delay_count as f64 / *completed_count as f64) * 100.0
         } else { 0.0 };
         
+        //savings per day of delay; undefined when projects finished on time or early on average
         let raw_score = if avg_delay > 0.0 {
-            (median_savings / avg_delay) * 100.0
-        } else { 0.0 };
+            numeric::ratio(median_savings, avg_delay).and_then(|score| numeric::finite(score * 100.0))
+        } else { None };
         
         results.push(RegionalStats {
-            region: region.clone(),
-            main_island: main_island.clone(),
+            region: region.to_string(),
+            main_island: dominant_island(island_counts).to_string(),
             total_budget: *total_budget,
             median_savings,
+            avg_duration,
             avg_delay,
             high_delay_pct,
             efficiency_score: raw_score, //store raw score, normalize later
         });
     }
 
-    let min_score = results.iter().map(|s| s.efficiency_score).fold(f64::INFINITY, f64::min);
-    let max_score = results.iter().map(|s| s.efficiency_score).fold(f64::NEG_INFINITY, f64::max);
+    //min and max over the defined scores only
+    let min_score = results.iter().filter_map(|s| s.efficiency_score).fold(f64::INFINITY, f64::min);
+    let max_score = results.iter().filter_map(|s| s.efficiency_score).fold(f64::NEG_INFINITY, f64::max);
     
     for stats in &mut results {
         stats.efficiency_score = normalize_score(stats.efficiency_score, min_score, max_score);
@@ -322,76 +528,141 @@ fn aggregate_regional_stats(projects: &Vec<FloodControlProject>) -> Vec<Regional
     results 
 }
 
-fn aggregate_contractor_stats(projects: &Vec<FloodControlProject>) -> Vec<ContractorStats> {
-    let mut results: Vec<ContractorStats> = Vec::new();
-    let mut contractor_map: HashMap<String, (u32, f64, i64, f64)> = HashMap::new();
-    // contractor: (project_count, total_contract_cost, total_completion_days, total_cost_savings)
+type ContractorEntry = (u32, f64, i64, i64, u32, f64);
 
-    for project in projects {
-        let entry = contractor_map.entry(project.contractor.clone()).or_insert((
+#[derive(Default)]
+struct ContractorAccumulator<K: NameKey = Name> {
+    contractor_map: HashMap<K, ContractorEntry>,
+    // contractor: (project_count, total_contract_cost, total_duration_days, total_delay_days, completed_count, total_cost_savings)
+}
+
+impl<K: NameKey> ContractorAccumulator<K> {
+    fn add(&mut self, project: &FloodControlProject) {
+        let entry = self.contractor_map.entry(K::from_name(&project.contractor)).or_insert((
             0,
             0.0,
             0,
+            0,
+            0,
             0.0,
         )); //initate entry if not exists
 
         entry.0 += 1;
         entry.1 += project.contract_cost;
-        entry.2 += project.completion_delay_days;
-        entry.3 += project.cost_savings;
+        if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+            entry.2 += duration_days;
+            entry.3 += delay_days;
+            entry.4 += 1;
+        }
+        entry.5 += project.cost_savings;
+    }
+
+    fn finish(self, min_projects: u32) -> Vec<ContractorStats> {
+        compute_contractor_stats(&self.contractor_map, min_projects)
+    }
+}
+
+fn aggregate_contractor_stats(projects: &[FloodControlProject], min_projects: u32) -> Vec<ContractorStats> {
+    let mut accumulator = ContractorAccumulator::<Name>::default();
+    for project in projects {
+        accumulator.add(project);
     }
+    accumulator.finish(min_projects)
+}
+
+fn compute_contractor_stats<K: NameKey>(contractor_map: &HashMap<K, ContractorEntry>, min_projects: u32) -> Vec<ContractorStats> {
+    let mut results: Vec<ContractorStats> = Vec::new();
 
-    for (contractor, (project_count, total_contract_cost, total_completion_days, total_cost_savings )) in &contractor_map {
-        if project_count < &5 {
-            continue; // skip contractors with less than 5 projects
+    for (contractor, (project_count, total_contract_cost, total_duration_days, total_delay_days, completed_count, total_cost_savings)) in contractor_map {
+        if *project_count < min_projects {
+            continue; // skip contractors with less than min_projects projects
         }
 
-        let avg_delay = //avg delay calculation
-        if *project_count > 0 { *total_completion_days as f64 / *project_count as f64 } else { 0.0 };
+        let avg_duration = //avg duration and delay calculation, over completed projects
+        if *completed_count > 0 { *total_duration_days as f64 / *completed_count as f64 } else { 0.0 };
+        let avg_delay = if *completed_count > 0 { *total_delay_days as f64 / *completed_count as f64 } else { 0.0 };
         
-        let reliability_index = (1.0 - (avg_delay / 90.0)) * (total_cost_savings / total_contract_cost) * 100.0;
+        let reliability_index = numeric::ratio(*total_cost_savings, *total_contract_cost)
+            .and_then(|savings_share| numeric::finite((1.0 - (avg_delay / 90.0)) * savings_share * 100.0));
+        let risk_flag = match reliability_index {
+            Some(index) if index < 50.0 => "High",
+            Some(_) => "Low",
+            None => "Undefined",
+        };
 
         results.push(ContractorStats {
-            contractor: contractor.clone(),
+            contractor: contractor.to_string(),
             project_count: *project_count,
             total_contract_cost: *total_contract_cost,
+            avg_duration_days: avg_duration,
             avg_completion_delay_days: avg_delay,
             total_cost_savings: *total_cost_savings,
             reliability_index,
-            risk_flag: if reliability_index < 50.0 { "High".to_string() } else { "Low".to_string() },
+            risk_flag: risk_flag.to_string(),
         });
     }
 
     results
 }
 
-fn aggregate_cost_stats(projects: &Vec<FloodControlProject>) -> Vec<CostOverrunStats> {
-    let mut results: Vec<CostOverrunStats> = Vec::new();
-    let mut cost_map: HashMap<(u32, String), (u32, u32, f64)> = HashMap::new();
-    // (funding_year, type_of_work): (total_projects,no. neg_cost_saving ,total_cost_savings)
+type CostEntry = (u32, u32, f64, i64, i64, u32);
 
-    for project in projects {
-        let key = (project.funding_year, project.type_of_work.clone());
-        let entry = cost_map.entry(key).or_insert((0, 0, 0.0));
+#[derive(Default)]
+struct CostAccumulator<K: NameKey = Name> {
+    cost_map: HashMap<(u32, K), CostEntry>,
+    // (funding_year, type_of_work): (total_projects,no. neg_cost_saving ,total_cost_savings, total_duration_days, total_delay_days, completed_count)
+}
+
+impl<K: NameKey> CostAccumulator<K> {
+    fn add(&mut self, project: &FloodControlProject) {
+        let key = (project.funding_year, K::from_name(&project.type_of_work));
+        let entry = self.cost_map.entry(key).or_insert((0, 0, 0.0, 0, 0, 0));
         entry.0 += 1;
         entry.2 += project.cost_savings;
+        if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+            entry.3 += duration_days;
+            entry.4 += delay_days;
+            entry.5 += 1;
+        }
 
         if project.cost_savings < 0.0 {
             entry.1 += 1; // count of cost overruns
         }
     }
 
-    for ((funding_year, type_of_work), (total_projects, negative_cost, total_cost_savings)) in &cost_map {
+    fn finish(self) -> Vec<CostOverrunStats> {
+        compute_cost_stats(&self.cost_map)
+    }
+}
+
+fn aggregate_cost_stats(projects: &[FloodControlProject]) -> Vec<CostOverrunStats> {
+    let mut accumulator = CostAccumulator::<Name>::default();
+    for project in projects {
+        accumulator.add(project);
+    }
+    accumulator.finish()
+}
+
+fn compute_cost_stats<K: NameKey>(cost_map: &HashMap<(u32, K), CostEntry>) -> Vec<CostOverrunStats> {
+    let mut results: Vec<CostOverrunStats> = Vec::new();
+
+    for ((funding_year, type_of_work), (total_projects, negative_cost, total_cost_savings, total_duration_days, total_delay_days, completed_count)) in cost_map {
         let avg_cost_savings = if *total_projects > 0 {  *total_cost_savings / *total_projects as f64 } else { 0.0 };
         let overrun_rate = if *total_projects > 0 { (*negative_cost as f64 / *total_projects as f64) * 100.0 } else { 0.0 };
+        let avg_duration_days = if *completed_count > 0 { *total_duration_days as f64 / *completed_count as f64 } else { 0.0 };
+        let avg_delay_days = if *completed_count > 0 { *total_delay_days as f64 / *completed_count as f64 } else { 0.0 };
 
         results.push(CostOverrunStats {
             funding_year: *funding_year,
-            type_of_work: type_of_work.clone(),
+            type_of_work: type_of_work.to_string(),
             total_projects: *total_projects,
             avg_cost_savings,
             overrun_rate,
-            yoy_change: 0.0, //not yet to be computed
+            avg_duration_days,
+            avg_delay_days,
+            yoy_change: None, //not yet to be computed
+            yoy_base_year: None,
+            cagr: None,
         });
     }
 
@@ -400,27 +671,15 @@ fn aggregate_cost_stats(projects: &Vec<FloodControlProject>) -> Vec<CostOverrunS
             .then(a.funding_year.cmp(&b.funding_year))
     });
 
-    for i in 0..results.len() {
+    //compare each type of work against its nearest earlier funding year
+    let points: Vec<(String, u32, f64)> = results.iter()
+        .map(|r| (r.type_of_work.clone(), r.funding_year, r.avg_cost_savings))
+        .collect();
 
-        if results[i].funding_year == 2021 {
-            results[i].yoy_change = 0.0;
-        }
-        else if i > 0 && 
-            results[i].type_of_work == results[i - 1].type_of_work && 
-            results[i].funding_year == results[i - 1].funding_year + 1 {
-
-            let prev_avg = (results[i-1].avg_cost_savings * 100.0).round() / 100.0;
-            let curr_avg = (results[i].avg_cost_savings * 100.0).round() / 100.0;
-            
-            if prev_avg.abs() < 0.01 { 
-                results[i].yoy_change = 0.0;
-            } else { 
-                results[i].yoy_change = ((curr_avg - prev_avg) / prev_avg) * 100.0;
-            }
-        }
-        else { 
-            results[i].yoy_change = 0.0; //no matching prior year data
-        }
+    for (stats, comparison) in results.iter_mut().zip(timecompare::compare_by_year(&points)) {
+        stats.yoy_change = comparison.yoy_change;
+        stats.yoy_base_year = comparison.base_year;
+        stats.cagr = comparison.cagr;
     }
 
     results
@@ -429,133 +688,147 @@ fn aggregate_cost_stats(projects: &Vec<FloodControlProject>) -> Vec<CostOverrunS
 fn display_flood_mitigation_report(regional_stats: &[RegionalStats]) {
     println!("\nRegional Flood Mitigation Efficiency Summary");
     println!("(Filtered: 2021-2023 Projects)");
-    println!("-----------------------------------------------------------------------------------------------------------------------------------------");
-    println!("| {:<35} | {:>10} | {:>20} | {:>15} | {:>10} | {:>10} | {:>15} |", 
-             "Region", "MainIsland", "TotalBudget", "MedianSavings", "AvgDelay", "HighDelay%", "EfficiencyScore");
-    println!("-----------------------------------------------------------------------------------------------------------------------------------------");
+    println!("------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<35} | {:>10} | {:>20} | {:>15} | {:>11} | {:>10} | {:>10} | {:>15} |", 
+             "Region", "MainIsland", "TotalBudget", "MedianSavings", "AvgDuration", "AvgDelay", "HighDelay%", "EfficiencyScore");
+    println!("------------------------------------------------------------------------------------------------------------------------------------------------------");
     
     for stats in regional_stats {
         let formatted_budget = format_currency(stats.total_budget);
         let formatted_savings = format_currency(stats.median_savings);
 
-        println!("| {:<35} | {:>10} | {:>20} | {:>15} | {:>10.2} | {:>10.2} | {:>15.2} |", 
+        println!("| {:<35} | {:>10} | {:>20} | {:>15} | {:>11.2} | {:>10.2} | {:>10.2} | {:>15} |", 
             stats.region,
             stats.main_island,
             formatted_budget,
             formatted_savings,
+            stats.avg_duration,
             stats.avg_delay,
             stats.high_delay_pct,
-            stats.efficiency_score
+            numeric::format_defined(stats.efficiency_score)
         );
     }
-    println!("-----------------------------------------------------------------------------------------------------------------------------------------");
+    println!("------------------------------------------------------------------------------------------------------------------------------------------------------");
 }
 
-fn display_contractor_performance_report(contractor_stats: &[ContractorStats]) {
+fn display_contractor_performance_report(contractor_stats: &[ContractorStats], ranked_by: &str) {
     println!("\nContractor Performance Summary (Top 15)");
-    println!("(Filtered: Contractors with at least 5 Projects, Ranked by Total Contract Cost)");
-    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
-    println!("| {:>4} | {:<85} | {:>8} | {:>17} | {:>8} | {:>15} | {:>12} | {:>8} |", 
-             "Rank", "Contractor", "Projects", "TotalCost", "AvgDelay", "TotalSavings", "Reliability", "RiskFlag");
-    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("(Filtered: Contractors with at least 5 Projects, Ranked by {})", ranked_by);
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:>4} | {:<85} | {:>8} | {:>17} | {:>11} | {:>8} | {:>15} | {:>12} | {:>8} |", 
+             "Rank", "Contractor", "Projects", "TotalCost", "AvgDuration", "AvgDelay", "TotalSavings", "Reliability", "RiskFlag");
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
     
-    let mut rank = 1;
-
-    for stats in contractor_stats {
+    for (index, stats) in contractor_stats.iter().enumerate() {
         let formatted_contract_cost = format_currency(stats.total_contract_cost);
         let formatted_cost_savings = format_currency(stats.total_cost_savings);
 
-        println!("| {:>4} | {:<85} | {:>8} | {:>17} | {:>8.2} | {:>15} | {:>12.2} | {:>8} |", 
-            rank,
+        println!("| {:>4} | {:<85} | {:>8} | {:>17} | {:>11.2} | {:>8.2} | {:>15} | {:>12} | {:>8} |", 
+            index + 1,
             stats.contractor,
             stats.project_count,
             formatted_contract_cost,
+            stats.avg_duration_days,
             stats.avg_completion_delay_days,
             formatted_cost_savings,
-            stats.reliability_index,
+            numeric::format_defined(stats.reliability_index),
             stats.risk_flag
         );
-
-        rank += 1;
     }
-    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
 }
 
 fn display_cost_overrun_report(cost_stats: &[CostOverrunStats]) {
     println!("\nCost Overrun Analysis Summary");
     println!("(Filtered: 2021-2023 Projects)");
-    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
-    println!("| {:<12} | {:<60} | {:>12} | {:>15} | {:>12} | {:>15} |", 
-             "FundingYear", "TypeOfWork", "Projects", "AvgSavings", "OverrunRate", "YoYChange");
-    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<12} | {:<60} | {:>12} | {:>15} | {:>12} | {:>11} | {:>8} | {:>15} | {:>8} | {:>10} |", 
+             "FundingYear", "TypeOfWork", "Projects", "AvgSavings", "OverrunRate", "AvgDuration", "AvgDelay", "YoYChange", "vs Year", "CAGR");
+    println!("------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
     
     for stats in cost_stats {
         let formatted_avg_savings = format_currency(stats.avg_cost_savings);
-        let formatted_yoy_change = format_currency(stats.yoy_change);
 
-        println!("| {:<12} | {:<60} | {:>12} | {:>15} | {:>12.2} | {:>15} |", 
+        println!("| {:<12} | {:<60} | {:>12} | {:>15} | {:>12.2} | {:>11.2} | {:>8.2} | {:>15} | {:>8} | {:>10} |", 
             stats.funding_year,
             stats.type_of_work,
             stats.total_projects,
             formatted_avg_savings,
             stats.overrun_rate,
-            formatted_yoy_change
+            stats.avg_duration_days,
+            stats.avg_delay_days,
+            timecompare::format_change(stats.yoy_change),
+            timecompare::format_year(stats.yoy_base_year),
+            timecompare::format_change(stats.cagr)
         );
     }
-    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
 }
 
-fn regional_flood_mitgation_report(projects: &Vec<FloodControlProject>) -> Result<(), Box<dyn Error>> {
-    let mut regional_stats = aggregate_regional_stats(projects);
-    
-    regional_stats.sort_by(|a, b| b.efficiency_score.partial_cmp(&a.efficiency_score).unwrap_or(Ordering::Equal));
+fn build_regional_report(projects: &[FloodControlProject], keys: &[SortKey]) -> Vec<RegionalStats> {
+    rank_regional_stats(aggregate_regional_stats(projects), keys)
+}
 
-    display_flood_mitigation_report(&regional_stats);
-    export_regional_stats_csv(&regional_stats, "report1_regional_summary.csv")?;
-    Ok(())
+//sorted by the configured keys, then by name; see ordering.rs
+fn rank_regional_stats(mut regional_stats: Vec<RegionalStats>, keys: &[SortKey]) -> Vec<RegionalStats> {
+    ordering::sort_rows(&mut regional_stats, keys);
+    regional_stats
 }
 
-fn contractor_performance_report(projects: &Vec<FloodControlProject>) -> Result<(), Box<dyn Error>> {
-    let mut contractor_stats: Vec<ContractorStats> = aggregate_contractor_stats(projects);
-    
-    contractor_stats.sort_by(|a,b| b.total_contract_cost.partial_cmp(&a.total_contract_cost).unwrap_or(Ordering::Equal));
-    contractor_stats.truncate(15);
-    
-    display_contractor_performance_report(&contractor_stats);
-    export_contractor_stats_csv(&contractor_stats, "report2_contractor_ranking.csv")?;
+fn build_contractor_report(projects: &[FloodControlProject], min_projects: u32, top: usize, keys: &[SortKey]) -> Vec<ContractorStats> {
+    rank_contractor_stats(aggregate_contractor_stats(projects, min_projects), top, keys)
+}
+
+fn rank_contractor_stats(mut contractor_stats: Vec<ContractorStats>, top: usize, keys: &[SortKey]) -> Vec<ContractorStats> {
+    ordering::sort_rows(&mut contractor_stats, keys);
+    contractor_stats.truncate(top);
+    contractor_stats
+}
+
+fn build_cost_report(projects: &[FloodControlProject], keys: &[SortKey]) -> Vec<CostOverrunStats> {
+    rank_cost_stats(aggregate_cost_stats(projects), keys)
+}
+
+fn rank_cost_stats(mut cost_stats: Vec<CostOverrunStats>, keys: &[SortKey]) -> Vec<CostOverrunStats> {
+    ordering::sort_rows(&mut cost_stats, keys);
+    cost_stats
+}
+
+fn regional_flood_mitgation_report(regional_stats: &[RegionalStats]) -> Result<(), Box<dyn Error>> {
+    display_flood_mitigation_report(regional_stats);
+    export_regional_stats_csv(regional_stats, "report1_regional_summary.csv")?;
     Ok(())
 }
 
-fn cost_overrun_report(projects: &Vec<FloodControlProject>) -> Result<(), Box<dyn Error>> {
-    let mut cost_stats: Vec<CostOverrunStats> = aggregate_cost_stats(projects);
-    
-    cost_stats.sort_by(|a, b| {
-        a.funding_year.cmp(&b.funding_year)
-            .then(b.avg_cost_savings.partial_cmp(&a.avg_cost_savings).unwrap_or(Ordering::Equal))
-    });
+fn contractor_performance_report(contractor_stats: &[ContractorStats], sorting: &SortConfig) -> Result<(), Box<dyn Error>> {
+    display_contractor_performance_report(contractor_stats, &ordering::describe::<ContractorStats>(&sorting.contractors));
+    export_contractor_stats_csv(contractor_stats, "report2_contractor_ranking.csv")?;
+    Ok(())
+}
 
-    display_cost_overrun_report(&cost_stats);
-    export_cost_overrun_stats_csv(&cost_stats, "report3_annual_trends.csv")?;
+fn cost_overrun_report(cost_stats: &[CostOverrunStats]) -> Result<(), Box<dyn Error>> {
+    display_cost_overrun_report(cost_stats);
+    export_cost_overrun_stats_csv(cost_stats, "report3_annual_trends.csv")?;
     Ok(())
 }
 
 fn export_regional_stats_csv(data: &[RegionalStats], filename: &str) -> Result<(), Box<dyn Error>> {
-    let output_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join(filename);
-    let mut writer = Writer::from_path(output_path)?;
+    let mut writer = Writer::from_path(data_path(filename))?;
     
     //header
-    writer.write_record(&["Region", "MainIsland", "TotalBudget", "MedianSavings", "AvgDelay", "HighDelay%", "EfficiencyScore"])?;
+    writer.write_record(["Region", "MainIsland", "TotalBudget", "MedianSavings", "AvgDuration", "AvgDelay", "HighDelay%", "EfficiencyScore"])?;
     
     //format and write data
     for stat in data {
-        writer.write_record(&[
+        writer.write_record([
             &stat.region,
             &stat.main_island,
             &format_currency(stat.total_budget),
             &format_currency(stat.median_savings),
+            &format!("{:.2}", stat.avg_duration),
             &format!("{:.2}", stat.avg_delay),
             &format!("{:.2}", stat.high_delay_pct),
-            &format!("{:.2}", stat.efficiency_score),
+            &numeric::format_defined(stat.efficiency_score),
         ])?;
     }
     
@@ -565,21 +838,21 @@ fn export_regional_stats_csv(data: &[RegionalStats], filename: &str) -> Result<(
 }
 
 fn export_contractor_stats_csv(data: &[ContractorStats], filename: &str) -> Result<(), Box<dyn Error>> {
-    let output_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join(filename);
-    let mut writer = Writer::from_path(output_path)?;
+    let mut writer = Writer::from_path(data_path(filename))?;
     
     //header
-    writer.write_record(&["Contractor", "ProjectCount", "TotalContractCost", "AvgCompletionDelayDays", "TotalCostSavings", "ReliabilityIndex", "RiskFlag"])?;
+    writer.write_record(["Contractor", "ProjectCount", "TotalContractCost", "AvgDurationDays", "AvgCompletionDelayDays", "TotalCostSavings", "ReliabilityIndex", "RiskFlag"])?;
     
     //format and write data
     for stat in data {
-        writer.write_record(&[
+        writer.write_record([
             &stat.contractor,
             &stat.project_count.to_string(),
             &format_currency(stat.total_contract_cost),
+            &format!("{:.2}", stat.avg_duration_days),
             &format!("{:.2}", stat.avg_completion_delay_days),
             &format_currency(stat.total_cost_savings),
-            &format!("{:.2}", stat.reliability_index),
+            &numeric::format_defined(stat.reliability_index),
             &stat.risk_flag,
         ])?;
     }
@@ -590,21 +863,24 @@ fn export_contractor_stats_csv(data: &[ContractorStats], filename: &str) -> Resu
 }
 
 fn export_cost_overrun_stats_csv(data: &[CostOverrunStats], filename: &str) -> Result<(), Box<dyn Error>> {
-    let output_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join(filename);
-    let mut writer = Writer::from_path(output_path)?;
+    let mut writer = Writer::from_path(data_path(filename))?;
     
     //header
-    writer.write_record(&["FundingYear", "TypeOfWork", "TotalProjects", "AvgCostSavings", "OverrunRate", "YoYChange"])?;
+    writer.write_record(["FundingYear", "TypeOfWork", "TotalProjects", "AvgCostSavings", "OverrunRate", "AvgDurationDays", "AvgDelayDays", "YoYChange", "YoYBaseYear", "CAGR"])?;
     
     //format and write data
     for stat in data {
-        writer.write_record(&[
+        writer.write_record([
             &stat.funding_year.to_string(),
             &stat.type_of_work,
             &stat.total_projects.to_string(),
             &format_currency(stat.avg_cost_savings),
             &format!("{:.2}", stat.overrun_rate),
-            &format!("{:.2}", stat.yoy_change),
+            &format!("{:.2}", stat.avg_duration_days),
+            &format!("{:.2}", stat.avg_delay_days),
+            &timecompare::format_change(stat.yoy_change),
+            &timecompare::format_year(stat.yoy_base_year),
+            &timecompare::format_change(stat.cagr),
         ])?;
     }
     
@@ -613,50 +889,162 @@ fn export_cost_overrun_stats_csv(data: &[CostOverrunStats], filename: &str) -> R
     Ok(())
 }
 
-fn generate_summary_json(projects: &Vec<FloodControlProject>) -> Result<(), Box<dyn Error>> {
-    let total_projects = projects.len();
-    
-    let contractors: HashSet<&String> = projects.iter().map(|p| &p.contractor).collect();
-    let total_contractors = contractors.len();
-    
-    let provinces: HashSet<&String> = projects.iter().map(|p| &p.province).collect();
-    let total_provinces = provinces.len();
-    
-    let regions: HashSet<&String> = projects.iter().map(|p| &p.region).collect();
-    let total_regions = regions.len();
-    
-    let total_delay: f64 = projects.iter().map(|p| p.completion_delay_days as f64).sum();
-    let global_average_delay_days = if total_projects > 0 { total_delay / total_projects as f64 } else { 0.0 };
-    
-    let total_savings: f64 = projects.iter().map(|p| p.cost_savings) .sum();
-    
-    let total_budget: f64 = projects.iter().map(|p| p.contract_cost).sum();
-    
-    let date_range = "2021-2023".to_string();
-    
-    let summary = Summary {
-        total_projects,
-        total_contractors,
-        total_provinces,
-        total_regions,
-        global_average_delay_days: (global_average_delay_days * 100.0).round() / 100.0,
-        total_savings: (total_savings * 100.0).round() / 100.0, 
-        total_budget: (total_budget * 100.0).round() / 100.0, 
-        date_range,
-    };
-    
-    let output_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join("summary.json");
-    let json_string = serde_json::to_string_pretty(&summary)?;
+#[derive(Default)]
+struct SummaryAccumulator<K: NameKey = Name> {
+    total_projects: usize,
+    status_counts: [usize; status::ALL_STATUSES.len()],
+    contractors: HashSet<K>,
+    provinces: HashSet<K>,
+    regions: HashSet<K>,
+    total_duration: f64,
+    total_delay: f64,
+    total_savings: f64,
+    total_budget: f64,
+}
+
+impl<K: NameKey> SummaryAccumulator<K> {
+    fn add(&mut self, project: &FloodControlProject) {
+        self.total_projects += 1;
+        self.contractors.insert(K::from_name(&project.contractor));
+        self.provinces.insert(K::from_name(&project.province));
+        self.regions.insert(K::from_name(&project.region));
+        self.status_counts[project.status as usize] += 1;
+        if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+            self.total_duration += duration_days as f64;
+            self.total_delay += delay_days as f64;
+        }
+        self.total_savings += project.cost_savings;
+        self.total_budget += project.contract_cost;
+    }
+
+    fn finish(self) -> Summary {
+        let count = |status: ProjectStatus| self.status_counts[status as usize];
+        let completed_projects = count(ProjectStatus::Completed);
+        let global_average_duration_days = if completed_projects > 0 { self.total_duration / completed_projects as f64 } else { 0.0 };
+        let global_average_delay_days = if completed_projects > 0 { self.total_delay / completed_projects as f64 } else { 0.0 };
+
+        let date_range = "2021-2023".to_string();
+
+        Summary {
+            total_projects: self.total_projects,
+            ongoing_projects: count(ProjectStatus::Ongoing),
+            invalid_timeline_projects: count(ProjectStatus::CompletionBeforeStart) + count(ProjectStatus::ImplausiblyLong),
+            total_contractors: self.contractors.len(),
+            total_provinces: self.provinces.len(),
+            total_regions: self.regions.len(),
+            global_average_duration_days: (global_average_duration_days * 100.0).round() / 100.0,
+            global_average_delay_days: (global_average_delay_days * 100.0).round() / 100.0,
+            total_savings: (self.total_savings * 100.0).round() / 100.0, 
+            total_budget: (self.total_budget * 100.0).round() / 100.0, 
+            date_range,
+        }
+    }
+}
+
+fn compute_summary(projects: &[FloodControlProject]) -> Summary {
+    let mut accumulator = SummaryAccumulator::<Name>::default();
+    for project in projects {
+        accumulator.add(project);
+    }
+    accumulator.finish()
+}
+
+fn generate_summary_json(summary: &Summary) -> Result<(), Box<dyn Error>> {
+    let output_path = data_path("summary.json");
+    let json_string = serde_json::to_string_pretty(summary)?;
     std::fs::write(output_path, json_string)?;
     
     println!("✓ Exported summary to data/summary.json");
     Ok(())
 }
 
+//the three required reports and the summary, as generated by menu option [2]
+fn generate_all_reports(projects: &[FloodControlProject], sorting: &SortConfig) -> Result<(), Box<dyn Error>> {
+    regional_flood_mitgation_report(&build_regional_report(projects, &sorting.regional))?;
+    contractor_performance_report(&build_contractor_report(projects, MIN_CONTRACTOR_PROJECTS, TOP_CONTRACTORS, &sorting.contractors), sorting)?;
+    cost_overrun_report(&build_cost_report(projects, &sorting.trends))?;
+    generate_summary_json(&compute_summary(projects))?;
+    Ok(())
+}
+
+//in any computers, this program can be run without changing the path of the csv file
+fn data_path(filename: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join(filename)
+}
+
+fn default_csv_path() -> PathBuf {
+    data_path("dpwh_flood_control_projects.csv")
+}
+
+fn print_usage() {
+    println!("Usage: fcp_data_analysis [command]");
+    println!("  (no command)     Start the interactive menu");
+    println!("  serve [port]     Load the data file and serve JSON on http://127.0.0.1:<port> (default {})", server::DEFAULT_PORT);
+    println!("  drill region <name> | drill contractor <name> | drill type <type of work> <year>");
+    println!("                   List and export the projects behind one report row");
+    println!("  diff <old.csv> <new.csv>");
+    println!("                   Compare two extracts: added/removed/modified projects and stat movement");
+    println!("  stream [file]    Generate the three reports and summary in one streaming pass, with throughput");
+    println!("                   (no rows are kept; memory still grows by about 100 bytes per row)");
+    println!("  bench [file]     Time the parse, duplicate check and report stages and measure interned name memory");
+    println!("  compare [dir]    Compare reports exported earlier in <dir> (default data/) with a fresh run");
+    for (name, label) in ADDITIONAL_REPORTS {
+        println!("  {:<16} {}", name, label);
+    }
+    println!("  config           Print the effective settings (save as data/{} to change them)", config::CONFIG_FILENAME);
+}
+
+fn run_command(args: &[String], config: &AnalysisConfig) -> Result<(), Box<dyn Error>> {
+    match args[0].as_str() {
+        "serve" => {
+            let port = match args.get(1) {
+                Some(port) => port.parse::<u16>().map_err(|_| format!("Invalid port: {}", port))?,
+                None => server::DEFAULT_PORT,
+            };
+            let projects = read_csv_file(default_csv_path(), config)?;
+            server::serve(&projects, port, &config.sorting)
+        },
+        "drill" => {
+            let projects = read_csv_file(default_csv_path(), config)?;
+            drill::drill_command(&projects, &args[1..])
+        },
+        name if ADDITIONAL_REPORTS.iter().any(|(report, _)| *report == name) => {
+            let projects = read_csv_file(default_csv_path(), config)?;
+            run_additional_report(name, &projects, config)
+        },
+        "diff" => diff::diff_command(&args[1..], config),
+        "stream" => ingest::stream_command(&args[1..], &default_csv_path(), config),
+        "bench" => bench::bench_command(&args[1..], &default_csv_path(), config),
+        "compare" => {
+            let projects = read_csv_file(default_csv_path(), config)?;
+            compare::compare_command(&projects, &args[1..], &config.comparison, &config.sorting)
+        },
+        "config" => {
+            println!("{}", serde_json::to_string_pretty(config)?);
+            Ok(())
+        },
+        "help" | "--help" | "-h" => {
+            print_usage();
+            Ok(())
+        },
+        other => {
+            print_usage();
+            Err(format!("Unknown command: {}", other).into())
+        },
+    }
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        let config = config::load_config()?;
+        return run_command(&args, &config);
+    }
+
     let mut csv_path: PathBuf;
     let mut choice: u32;
     let mut projects: Option<Vec<FloodControlProject>> = None;
+    let config = config::load_config()?;
 
     println!("\n╔════════════════════════════════════════════════╗");
     println!("║  DPWH Flood Control Project Analysis System    ║");
@@ -664,8 +1052,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     println!("╚════════════════════════════════════════════════╝");
 
     loop {
-        //in any computers, this program can be run without changing the path of the csv file
-        csv_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join("dpwh_flood_control_projects.csv");
+        csv_path = default_csv_path();
         print_menu();
         print!("\nEnter your choice: ");
         io::stdout().flush().unwrap();
@@ -677,7 +1064,7 @@ fn main() -> Result<(), Box<dyn Error>> {
                     println!("✓ Data file already loaded. Ready to generate reports.");
                 }
                 else {
-                    projects = Some(read_csv_file(csv_path)?);
+                    projects = Some(read_csv_file(csv_path, &config)?);
                 }
             },
             2 => {
@@ -685,21 +1072,134 @@ fn main() -> Result<(), Box<dyn Error>> {
                     println!("\n>>> Generating all reports...\n");
                     println!("Output is saved to individual files");
 
-                    regional_flood_mitgation_report(data)?;
-                    contractor_performance_report(data)?;
-                    cost_overrun_report(data)?;
-                    generate_summary_json(data)?;
-
+                    generate_all_reports(data, &config.sorting)?;
                 }
                 else {
                     println!("⚠ Error: No data loaded. Please select option [1] to load the data file first.\n");
                 }
             },
             3 => {
+                if let Some(ref data) = projects {
+                    print_additional_reports_menu();
+                    print!("\nEnter your choice: ");
+                    io::stdout().flush().unwrap();
+
+                    match input_integer() {
+                        0 => {},
+                        n if (n as usize) <= ADDITIONAL_REPORTS.len() => {
+                            run_additional_report(ADDITIONAL_REPORTS[n as usize - 1].0, data, &config)?;
+                        },
+                        _ => println!("⚠ Invalid choice. Returning to main menu.\n"),
+                    }
+                }
+                else {
+                    println!("⚠ Error: No data loaded. Please select option [1] to load the data file first.\n");
+                }
+            },
+            4 => {
                 println!("Exiting program...\n");
                 break Ok(());
             },
-            _ => println!("⚠ Invalid choice. Please enter 1, 2, or 3.\n"),
+            _ => println!("⚠ Invalid choice. Please enter 1, 2, 3, or 4.\n"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean(raw: RawFloodControlProject) -> Option<FloodControlProject> {
+        clean_project(raw, &AnalysisConfig::default(), &mut MoneyCounts::default(), &mut Interner::default())
+    }
+
+    #[test]
+    fn planned_duration_prefers_the_target_date() {
+        let config = DurationConfig::default();
+        let start = NaiveDate::from_ymd_opt(2022, 3, 1).unwrap();
+        let raw = RawFloodControlProject::sample(&[("TargetCompletionDate", "2022-04-30"), ("ContractDuration", "120")]);
+        assert_eq!(planned_duration(&raw, start, &config), 60);
+        assert_eq!(planned_duration(&RawFloodControlProject::sample(&[("ContractDuration", "120 CD")]), start, &config), 120);
+    }
+
+    #[test]
+    fn planned_duration_falls_back_to_the_standard_for_the_work() {
+        let config = DurationConfig::default();
+        let start = NaiveDate::from_ymd_opt(2022, 3, 1).unwrap();
+        assert_eq!(planned_duration(&RawFloodControlProject::sample(&[("ContractDuration", "0")]), start, &config), 240);
+        assert_eq!(planned_duration(&RawFloodControlProject::sample(&[("ContractDuration", "TBD")]), start, &config), 240);
+        assert_eq!(planned_duration(&RawFloodControlProject::sample(&[("TypeOfWork", "Dredging")]), start, &config), config.default_days);
+    }
+
+    #[test]
+    fn delay_is_measured_against_the_planned_duration() {
+        //250 days against the standard 240
+        let late = clean(RawFloodControlProject::sample(&[])).unwrap();
+        assert_eq!((late.duration_days, late.planned_duration_days, late.delay_days), (Some(250), 240, Some(10)));
+
+        let early = clean(RawFloodControlProject::sample(&[("ContractDuration", "300")])).unwrap();
+        assert_eq!(early.delay_days, Some(-50));
+
+        let ongoing = clean(RawFloodControlProject::sample(&[("ActualCompletionDate", "")])).unwrap();
+        assert_eq!((ongoing.duration_days, ongoing.delay_days), (None, None));
+        assert_eq!(ongoing.status, ProjectStatus::Ongoing);
+    }
+
+    #[test]
+    fn cleaning_drops_rows_outside_the_window_or_without_amounts() {
+        assert!(clean(RawFloodControlProject::sample(&[("FundingYear", "2020")])).is_none());
+        assert!(clean(RawFloodControlProject::sample(&[("StartDate", "")])).is_none());
+        assert!(clean(RawFloodControlProject::sample(&[("ContractCost", "0")])).is_none());
+    }
+
+    #[test]
+    fn scores_without_spread_are_undefined() {
+        assert_eq!(normalize_score(Some(5.0), 1.0, 9.0), Some(50.0));
+        assert_eq!(normalize_score(Some(5.0), 5.0, 5.0), None);
+        assert_eq!(normalize_score(None, 1.0, 9.0), None);
+        //no defined scores at all: min and max are still the fold's infinities
+        assert_eq!(normalize_score(None, f64::INFINITY, f64::NEG_INFINITY), None);
+    }
+
+    #[test]
+    fn efficiency_is_undefined_without_delay() {
+        let sample = |id: &str, region: &str, delay_days: i64| {
+            let mut project = FloodControlProject::sample(id, region, "Builder A");
+            project.delay_days = Some(delay_days);
+            project
+        };
+        let projects = [sample("P1", "Region I", 10), sample("P2", "Region II", 40), sample("P3", "Region III", 0)];
+        let report = build_regional_report(&projects, &SortConfig::default().regional);
+        let score = |region: &str| report.iter().find(|r| r.region == region).unwrap().efficiency_score;
+
+        assert_eq!((score("Region I"), score("Region II"), score("Region III")), (Some(100.0), Some(0.0), None));
+        //a single defined score has no spread to normalize over
+        assert_eq!(build_regional_report(&projects[..1], &[])[0].efficiency_score, None);
+    }
+
+    #[test]
+    fn owned_keys_group_like_interned_names() {
+        let projects = [
+            FloodControlProject::sample("P1", "Region I", "Builder A"),
+            FloodControlProject::sample("P2", "Region I", "Builder B"),
+            FloodControlProject::sample("P3", "Region II", "Builder A"),
+        ];
+        let mut interned: ContractorAccumulator = ContractorAccumulator::default();
+        let mut owned: ContractorAccumulator<String> = ContractorAccumulator::default();
+        let mut interned_regions: RegionalAccumulator = RegionalAccumulator::default();
+        let mut owned_regions: RegionalAccumulator<String> = RegionalAccumulator::default();
+        for project in &projects {
+            interned.add(project);
+            owned.add(project);
+            interned_regions.add(project);
+            owned_regions.add(project);
         }
+
+        let rows = |stats: Vec<ContractorStats>| rank_contractor_stats(stats, TOP_CONTRACTORS, &[])
+            .into_iter().map(|s| (s.contractor, s.project_count)).collect::<Vec<_>>();
+        assert_eq!(rows(interned.finish(1)), rows(owned.finish(1)));
+        let regions = |stats: Vec<RegionalStats>| rank_regional_stats(stats, &[])
+            .into_iter().map(|s| (s.region, s.total_budget)).collect::<Vec<_>>();
+        assert_eq!(regions(interned_regions.finish()), regions(owned_regions.finish()));
     }
 }
@@ -0,0 +1,323 @@
+// Benford's law screening of contract_cost and approved_budget.
+// First-digit and first-two-digit tests, scored with chi-square and the
+// mean absolute deviation (MAD) using Nigrini's conformity ranges.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::{data_path, FloodControlProject};
+
+//groups smaller than this are not tested; the two-digit test needs more data
+const MIN_FIRST_DIGIT_PROJECTS: usize = 50;
+const MIN_FIRST_TWO_DIGIT_PROJECTS: usize = 300;
+
+//chi-square critical values at alpha = 0.05 (df = 8 and df = 89)
+const FIRST_DIGIT_CHI_CRITICAL: f64 = 15.507;
+const FIRST_TWO_DIGIT_CHI_CRITICAL: f64 = 112.022;
+
+#[derive(Clone, Copy, PartialEq)]
+enum BenfordTest {
+    FirstDigit,
+    FirstTwoDigits,
+}
+
+impl BenfordTest {
+    fn name(self) -> &'static str {
+        match self {
+            BenfordTest::FirstDigit => "FirstDigit",
+            BenfordTest::FirstTwoDigits => "FirstTwoDigits",
+        }
+    }
+
+    fn digits(self) -> std::ops::RangeInclusive<u32> {
+        match self {
+            BenfordTest::FirstDigit => 1..=9,
+            BenfordTest::FirstTwoDigits => 10..=99,
+        }
+    }
+
+    fn min_projects(self) -> usize {
+        match self {
+            BenfordTest::FirstDigit => MIN_FIRST_DIGIT_PROJECTS,
+            BenfordTest::FirstTwoDigits => MIN_FIRST_TWO_DIGIT_PROJECTS,
+        }
+    }
+
+    fn chi_critical(self) -> f64 {
+        match self {
+            BenfordTest::FirstDigit => FIRST_DIGIT_CHI_CRITICAL,
+            BenfordTest::FirstTwoDigits => FIRST_TWO_DIGIT_CHI_CRITICAL,
+        }
+    }
+
+    //Nigrini (2012) MAD ranges
+    fn conformity(self, mad: f64) -> &'static str {
+        let limits = match self {
+            BenfordTest::FirstDigit => [0.006, 0.012, 0.015],
+            BenfordTest::FirstTwoDigits => [0.0012, 0.0018, 0.0022],
+        };
+
+        if mad < limits[0] {
+            "Close"
+        } else if mad < limits[1] {
+            "Acceptable"
+        } else if mad < limits[2] {
+            "Marginal"
+        } else {
+            "Nonconformity"
+        }
+    }
+
+    fn leading(self, amount: f64) -> Option<u32> {
+        let (first, second) = leading_digits(amount)?;
+        match self {
+            BenfordTest::FirstDigit => Some(first),
+            BenfordTest::FirstTwoDigits if amount >= 10.0 => Some(first * 10 + second),
+            BenfordTest::FirstTwoDigits => None,
+        }
+    }
+}
+
+struct BenfordResult {
+    scope: &'static str,
+    group: String,
+    field: &'static str,
+    test: BenfordTest,
+    sample_size: usize,
+    observed: BTreeMap<u32, usize>,
+    chi_square: f64,
+    mad: f64,
+}
+
+impl BenfordResult {
+    fn conformity(&self) -> &'static str {
+        self.test.conformity(self.mad)
+    }
+
+    fn is_nonconforming(&self) -> bool {
+        self.conformity() == "Nonconformity"
+    }
+}
+
+//first and second significant digits, read from the scientific notation to avoid float drift
+fn leading_digits(amount: f64) -> Option<(u32, u32)> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return None;
+    }
+
+    let mantissa = format!("{:e}", amount);
+    let mut digits = mantissa.chars().filter(|c| *c != '.').take_while(|c| c.is_ascii_digit());
+    let first = digits.next()?.to_digit(10)?;
+    let second = digits.next().and_then(|c| c.to_digit(10)).unwrap_or(0);
+    Some((first, second))
+}
+
+fn expected_proportion(digits: u32) -> f64 {
+    (1.0 + 1.0 / digits as f64).log10()
+}
+
+fn run_test(scope: &'static str, group: &str, field: &'static str, test: BenfordTest, amounts: &[f64]) -> Option<BenfordResult> {
+    let leading: Vec<u32> = amounts.iter().filter_map(|a| test.leading(*a)).collect();
+    if leading.len() < test.min_projects() {
+        return None;
+    }
+
+    let mut observed: BTreeMap<u32, usize> = test.digits().map(|d| (d, 0)).collect();
+    for digit in &leading {
+        *observed.entry(*digit).or_insert(0) += 1;
+    }
+
+    let n = leading.len() as f64;
+    let mut chi_square = 0.0;
+    let mut total_deviation = 0.0;
+
+    for (digit, count) in &observed {
+        let expected = expected_proportion(*digit);
+        let actual = *count as f64 / n;
+        chi_square += (*count as f64 - expected * n).powi(2) / (expected * n);
+        total_deviation += (actual - expected).abs();
+    }
+
+    Some(BenfordResult {
+        scope,
+        group: group.to_string(),
+        field,
+        test,
+        sample_size: leading.len(),
+        mad: total_deviation / observed.len() as f64,
+        observed,
+        chi_square,
+    })
+}
+
+fn test_groups<F>(projects: &[FloodControlProject], scope: &'static str, key: F) -> Vec<BenfordResult>
+where
+    F: Fn(&FloodControlProject) -> &str,
+{
+    let mut groups: BTreeMap<&str, (Vec<f64>, Vec<f64>)> = BTreeMap::new();
+    // group: (contract_costs, approved_budgets)
+    for project in projects {
+        let entry = groups.entry(key(project)).or_default();
+        entry.0.push(project.contract_cost);
+        entry.1.push(project.approved_budget);
+    }
+
+    let mut results: Vec<BenfordResult> = Vec::new();
+    for (group, (costs, budgets)) in &groups {
+        for test in [BenfordTest::FirstDigit, BenfordTest::FirstTwoDigits] {
+            results.extend(run_test(scope, group, "ContractCost", test, costs));
+            results.extend(run_test(scope, group, "ApprovedBudget", test, budgets));
+        }
+    }
+
+    results
+}
+
+fn display_benford_report(results: &[BenfordResult]) {
+    println!("\nBenford's Law Conformity (ContractCost / ApprovedBudget)");
+    println!("(Groups need {} projects for the first-digit test and {} for the first-two-digit test)",
+        MIN_FIRST_DIGIT_PROJECTS, MIN_FIRST_TWO_DIGIT_PROJECTS);
+    println!("----------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<10} | {:<45} | {:<14} | {:<14} | {:>6} | {:>10} | {:>8} | {:<14} |",
+             "Scope", "Group", "Field", "Test", "N", "ChiSquare", "MAD", "Conformity");
+    println!("----------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for result in results {
+        let group: String = result.group.chars().take(45).collect();
+
+        println!("| {:<10} | {:<45} | {:<14} | {:<14} | {:>6} | {:>10.2} | {:>8.4} | {:<14} |",
+            result.scope,
+            group,
+            result.field,
+            result.test.name(),
+            result.sample_size,
+            result.chi_square,
+            result.mad,
+            result.conformity()
+        );
+    }
+    println!("----------------------------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn export_benford_csv(results: &[&BenfordResult], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Scope", "Group", "Field", "Test", "SampleSize", "ChiSquare", "ChiSquareCritical", "ChiSquareExceeded",
+        "MAD", "Conformity"])?;
+
+    //format and write data
+    for result in results {
+        writer.write_record([
+            result.scope,
+            &result.group,
+            result.field,
+            result.test.name(),
+            &result.sample_size.to_string(),
+            &format!("{:.4}", result.chi_square),
+            &format!("{:.3}", result.test.chi_critical()),
+            if result.chi_square > result.test.chi_critical() { "Yes" } else { "No" },
+            &format!("{:.6}", result.mad),
+            result.conformity(),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+fn export_digit_frequencies_csv(results: &[&BenfordResult], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Field", "Test", "Digits", "ObservedCount", "Observed%", "Expected%"])?;
+
+    //format and write data
+    for result in results {
+        for (digits, count) in &result.observed {
+            writer.write_record([
+                result.field,
+                result.test.name(),
+                &digits.to_string(),
+                &count.to_string(),
+                &format!("{:.4}", (*count as f64 / result.sample_size as f64) * 100.0),
+                &format!("{:.4}", expected_proportion(*digits) * 100.0),
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn benford_report(projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    let mut results = test_groups(projects, "Overall", |_| "All projects");
+    let overall_count = results.len();
+    results.extend(test_groups(projects, "Region", |p| &p.region));
+    results.extend(test_groups(projects, "Contractor", |p| &p.contractor));
+
+    let all: Vec<&BenfordResult> = results.iter().collect();
+    let overall: Vec<&BenfordResult> = results.iter().take(overall_count).collect();
+    let nonconforming: Vec<&BenfordResult> = results.iter().filter(|r| r.is_nonconforming()).collect();
+
+    display_benford_report(&results);
+    println!("{} of {} tested groups are nonconforming.", nonconforming.len(), results.len());
+
+    export_benford_csv(&all, "report_benford.csv")?;
+    export_benford_csv(&nonconforming, "report_benford_nonconforming.csv")?;
+    export_digit_frequencies_csv(&overall, "report_benford_digits.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_leading_digits_without_float_drift() {
+        assert_eq!(leading_digits(1_234.5), Some((1, 2)));
+        assert_eq!(leading_digits(0.0029), Some((2, 9)));
+        assert_eq!(leading_digits(7.0), Some((7, 0)));
+        assert_eq!(leading_digits(0.0), None);
+        assert_eq!(leading_digits(-5.0), None);
+        assert_eq!(leading_digits(f64::NAN), None);
+    }
+
+    #[test]
+    fn two_digit_test_skips_amounts_below_ten() {
+        assert_eq!(BenfordTest::FirstTwoDigits.leading(9.5), None);
+        assert_eq!(BenfordTest::FirstTwoDigits.leading(95.0), Some(95));
+        assert_eq!(BenfordTest::FirstDigit.leading(9.5), Some(9));
+    }
+
+    #[test]
+    fn expected_proportions_sum_to_one() {
+        let first: f64 = BenfordTest::FirstDigit.digits().map(expected_proportion).sum();
+        let first_two: f64 = BenfordTest::FirstTwoDigits.digits().map(expected_proportion).sum();
+        assert!((first - 1.0).abs() < 1e-12);
+        assert!((first_two - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn benford_distributed_amounts_conform() {
+        //10^(i/n) spreads the mantissas exactly as Benford's law expects
+        let amounts: Vec<f64> = (0..1000).map(|i| 10f64.powf(3.0 + i as f64 / 1000.0)).collect();
+        let result = run_test("All", "All", "ContractCost", BenfordTest::FirstDigit, &amounts).unwrap();
+        assert_eq!(result.conformity(), "Close");
+
+        let uniform: Vec<f64> = (0..1000).map(|i| 1000.0 + (i % 9) as f64 * 1000.0).collect();
+        let result = run_test("All", "All", "ContractCost", BenfordTest::FirstDigit, &uniform).unwrap();
+        assert!(result.is_nonconforming());
+        assert!(result.chi_square > BenfordTest::FirstDigit.chi_critical());
+    }
+
+    #[test]
+    fn small_samples_are_not_tested() {
+        let amounts = vec![1_000.0; MIN_FIRST_DIGIT_PROJECTS - 1];
+        assert!(run_test("All", "All", "ContractCost", BenfordTest::FirstDigit, &amounts).is_none());
+    }
+}
@@ -0,0 +1,214 @@
+// Bid-to-budget ratio report (contract cost / approved budget for the contract).
+// Ratios bunched just under 100% of the ABC, or nearly identical across a
+// contractor's projects, are common bid-rigging indicators.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::statistics;
+use crate::timecompare;
+use crate::intern::Name;
+use crate::{data_path, FloodControlProject, MIN_CONTRACTOR_PROJECTS};
+
+//ratios from 99% up to 100% of the ABC count as "near ABC"
+const NEAR_ABC_RATIO: f64 = 99.0;
+//contractors whose ratio standard deviation is below this (percentage points) are flagged uniform
+const UNIFORM_STD_DEV_THRESHOLD: f64 = 0.5;
+//contractors with at least this share of near-ABC bids are flagged clustered
+const CLUSTERED_SHARE_THRESHOLD: f64 = 50.0;
+
+struct BidRatioStats {
+    group_by: &'static str,
+    group: String,
+    project_count: usize,
+    mean_ratio: f64,
+    median_ratio: f64,
+    std_dev: f64,
+    min_ratio: f64,
+    max_ratio: f64,
+    near_abc_share: f64,
+    over_abc_share: f64,
+    //change of the mean ratio against the nearest earlier funding year (year rows only)
+    mean_ratio_yoy: Option<f64>,
+    flags: Vec<&'static str>,
+}
+
+//contract cost as a percentage of the approved budget
+fn bid_ratio(project: &FloodControlProject) -> f64 {
+    (project.contract_cost / project.approved_budget) * 100.0
+}
+
+fn aggregate_bid_ratios<F>(projects: &[FloodControlProject], group_by: &'static str, key: F) -> Vec<BidRatioStats>
+where
+    F: Fn(&FloodControlProject) -> Name,
+{
+    let mut ratio_map: BTreeMap<Name, Vec<f64>> = BTreeMap::new();
+    for project in projects {
+        ratio_map.entry(key(project)).or_default().push(bid_ratio(project));
+    }
+
+    ratio_map
+        .into_iter()
+        .map(|(group, ratios)| {
+            let summary = statistics::describe(&ratios);
+            let count = ratios.len() as f64;
+            let near_abc = ratios.iter().filter(|r| (NEAR_ABC_RATIO..=100.0).contains(*r)).count() as f64;
+            let over_abc = ratios.iter().filter(|r| **r > 100.0).count() as f64;
+
+            BidRatioStats {
+                group_by,
+                group: group.to_string(),
+                project_count: ratios.len(),
+                mean_ratio: summary.mean,
+                median_ratio: summary.median,
+                std_dev: summary.std_dev,
+                min_ratio: summary.min,
+                max_ratio: summary.max,
+                near_abc_share: (near_abc / count) * 100.0,
+                over_abc_share: (over_abc / count) * 100.0,
+                mean_ratio_yoy: None,
+                flags: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+fn compare_years(year_stats: &mut [BidRatioStats]) {
+    let points: Vec<((), u32, f64)> = year_stats
+        .iter()
+        .map(|s| ((), s.group.parse::<u32>().unwrap_or_default(), s.mean_ratio))
+        .collect();
+
+    for (stats, comparison) in year_stats.iter_mut().zip(timecompare::compare_by_year(&points)) {
+        stats.mean_ratio_yoy = comparison.yoy_change;
+    }
+}
+
+fn flag_contractors(contractor_stats: &mut Vec<BidRatioStats>) {
+    contractor_stats.retain(|c| c.project_count >= MIN_CONTRACTOR_PROJECTS as usize);
+
+    for stats in contractor_stats.iter_mut() {
+        if stats.std_dev < UNIFORM_STD_DEV_THRESHOLD {
+            stats.flags.push("UniformRatios");
+        }
+        if stats.near_abc_share >= CLUSTERED_SHARE_THRESHOLD {
+            stats.flags.push("ClusteredNearABC");
+        }
+    }
+}
+
+fn display_bid_ratio_report(title: &str, stats: &[&BidRatioStats]) {
+    println!("\n{}", title);
+    println!("---------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<55} | {:>8} | {:>8} | {:>8} | {:>8} | {:>10} | {:>10} | {:<25} |",
+             "Group", "Projects", "Mean%", "Median%", "StdDev", "99-100%", ">100%", "Flags");
+    println!("---------------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for stat in stats {
+        let group: String = stat.group.chars().take(55).collect();
+
+        println!("| {:<55} | {:>8} | {:>8.2} | {:>8.2} | {:>8.2} | {:>10.2} | {:>10.2} | {:<25} |",
+            group,
+            stat.project_count,
+            stat.mean_ratio,
+            stat.median_ratio,
+            stat.std_dev,
+            stat.near_abc_share,
+            stat.over_abc_share,
+            stat.flags.join(", ")
+        );
+    }
+    println!("---------------------------------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn export_bid_ratio_csv(stats: &[&BidRatioStats], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["GroupBy", "Group", "ProjectCount", "MeanRatio%", "MedianRatio%", "StdDev", "MinRatio%", "MaxRatio%",
+        "Share99to100%", "ShareOver100%", "MeanRatioYoY", "Flags"])?;
+
+    //format and write data
+    for stat in stats {
+        writer.write_record([
+            stat.group_by,
+            &stat.group,
+            &stat.project_count.to_string(),
+            &format!("{:.2}", stat.mean_ratio),
+            &format!("{:.2}", stat.median_ratio),
+            &format!("{:.4}", stat.std_dev),
+            &format!("{:.2}", stat.min_ratio),
+            &format!("{:.2}", stat.max_ratio),
+            &format!("{:.2}", stat.near_abc_share),
+            &format!("{:.2}", stat.over_abc_share),
+            &timecompare::format_change(stat.mean_ratio_yoy),
+            &stat.flags.join("; "),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn bid_ratio_report(projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    let region_stats = aggregate_bid_ratios(projects, "Region", |p| p.region.clone());
+    let mut year_stats = aggregate_bid_ratios(projects, "FundingYear", |p| p.funding_year.to_string().into());
+    compare_years(&mut year_stats);
+    let mut contractor_stats = aggregate_bid_ratios(projects, "Contractor", |p| p.contractor.clone());
+    flag_contractors(&mut contractor_stats);
+
+    let regions: Vec<&BidRatioStats> = region_stats.iter().collect();
+    let years: Vec<&BidRatioStats> = year_stats.iter().collect();
+    let flagged: Vec<&BidRatioStats> = contractor_stats.iter().filter(|c| !c.flags.is_empty()).collect();
+    let all: Vec<&BidRatioStats> = region_stats.iter().chain(&year_stats).chain(&contractor_stats).collect();
+
+    display_bid_ratio_report("Bid-to-Budget Ratio by Region (ContractCost / ApprovedBudget)", &regions);
+    display_bid_ratio_report("Bid-to-Budget Ratio by Funding Year", &years);
+    display_bid_ratio_report(&format!("Contractors with Abnormal Bid Ratios (at least {} projects)", MIN_CONTRACTOR_PROJECTS), &flagged);
+
+    export_bid_ratio_csv(&all, "report_bid_ratio.csv")?;
+    export_bid_ratio_csv(&flagged, "report_bid_ratio_flagged_contractors.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projects_with_costs(contractor: &str, costs: &[f64]) -> Vec<FloodControlProject> {
+        costs
+            .iter()
+            .enumerate()
+            .map(|(i, cost)| {
+                let mut project = FloodControlProject::sample(&format!("{}-{}", contractor, i), "Region I", contractor);
+                project.contract_cost = *cost;
+                project
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_bids_near_and_over_the_budget() {
+        let projects = projects_with_costs("Builder A", &[990_000.0, 1_000_000.0, 1_010_000.0, 500_000.0]);
+        let stats = aggregate_bid_ratios(&projects, "Contractor", |p| p.contractor.clone());
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].near_abc_share, 50.0);
+        assert_eq!(stats[0].over_abc_share, 25.0);
+        assert_eq!(stats[0].max_ratio, 101.0);
+    }
+
+    #[test]
+    fn flags_uniform_and_clustered_contractors_with_enough_projects() {
+        let mut projects = projects_with_costs("Builder A", &[995_000.0; 5]);
+        projects.extend(projects_with_costs("Builder B", &[995_000.0; 2]));
+        projects.extend(projects_with_costs("Builder C", &[500_000.0, 600_000.0, 700_000.0, 800_000.0, 900_000.0]));
+        let mut stats = aggregate_bid_ratios(&projects, "Contractor", |p| p.contractor.clone());
+        flag_contractors(&mut stats);
+
+        let flags: Vec<(&str, &Vec<&str>)> = stats.iter().map(|s| (s.group.as_str(), &s.flags)).collect();
+        assert_eq!(flags, vec![("Builder A", &vec!["UniformRatios", "ClusteredNearABC"]), ("Builder C", &vec![])]);
+    }
+}
@@ -0,0 +1,318 @@
+// Comparison of previously exported reports (report1/2/3 CSVs and summary.json)
+// against a fresh run: contractor rank changes, newly High risk contractors,
+// regional efficiency movement, overrun rate movement and summary figures.
+// What counts as significant is set in the "comparison" section of the config.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use csv::{ReaderBuilder, Writer};
+
+use crate::config::{ComparisonConfig, SortConfig};
+use crate::{
+    build_contractor_report, build_cost_report, build_regional_report, compute_summary, data_path, timecompare,
+    FloodControlProject, MIN_CONTRACTOR_PROJECTS, TOP_CONTRACTORS,
+};
+
+//one exported CSV row keyed by column name
+type ReportRow = HashMap<String, String>;
+
+struct ComparisonRow {
+    report: &'static str,
+    item: String,
+    metric: String,
+    previous: String,
+    current: String,
+    change: String,
+    significant: bool,
+}
+
+//None when the file was not exported
+fn read_previous_csv(dir: &Path, filename: &str) -> Result<Option<Vec<ReportRow>>, Box<dyn Error>> {
+    let path = dir.join(filename);
+    if !path.exists() {
+        println!("- {} not found, skipped", path.display());
+        return Ok(None);
+    }
+
+    let mut reader = ReaderBuilder::new().has_headers(true).from_path(&path)?;
+    let headers = reader.headers()?.clone();
+    let mut rows: Vec<ReportRow> = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        rows.push(headers.iter().map(String::from).zip(record.iter().map(String::from)).collect());
+    }
+    Ok(Some(rows))
+}
+
+//reports are exported with thousands separators and "N/A" for undefined values
+fn parse_number(value: &str) -> Option<f64> {
+    value.replace(',', "").trim().parse::<f64>().ok()
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "N/A".to_string())
+}
+
+fn column<'a>(row: &'a ReportRow, name: &str) -> &'a str {
+    row.get(name).map(String::as_str).unwrap_or("")
+}
+
+//rows for a metric compared as a difference; a row missing on either side is always significant
+fn compare_values(report: &'static str, metric: &'static str, previous: &BTreeMap<String, Option<f64>>,
+                  current: &BTreeMap<String, Option<f64>>, threshold: f64) -> Vec<ComparisonRow> {
+    let items: BTreeSet<&String> = previous.keys().chain(current.keys()).collect();
+
+    items
+        .into_iter()
+        .map(|item| {
+            let old = previous.get(item).copied().flatten();
+            let new = current.get(item).copied().flatten();
+            let change = match (old, new) {
+                (Some(o), Some(n)) => Some(n - o),
+                _ => None,
+            };
+            ComparisonRow {
+                report,
+                item: item.clone(),
+                metric: metric.to_string(),
+                previous: if previous.contains_key(item) { format_optional(old) } else { "Absent".to_string() },
+                current: if current.contains_key(item) { format_optional(new) } else { "Absent".to_string() },
+                change: format_optional(change),
+                significant: change.is_none_or(|c| c.abs() >= threshold),
+            }
+        })
+        .collect()
+}
+
+fn compare_contractors(previous: &[ReportRow], projects: &[FloodControlProject], config: &ComparisonConfig, sorting: &SortConfig) -> Vec<ComparisonRow> {
+    let current = build_contractor_report(projects, MIN_CONTRACTOR_PROJECTS, TOP_CONTRACTORS, &sorting.contractors);
+
+    //(rank, risk flag) by contractor; the exported file is already in rank order
+    let previous_ranks: HashMap<&str, (usize, &str)> = previous
+        .iter()
+        .enumerate()
+        .map(|(index, row)| (column(row, "Contractor"), (index + 1, column(row, "RiskFlag"))))
+        .collect();
+    let current_ranks: HashMap<&str, (usize, &str)> = current
+        .iter()
+        .enumerate()
+        .map(|(index, stats)| (stats.contractor.as_str(), (index + 1, stats.risk_flag.as_str())))
+        .collect();
+
+    let mut names: Vec<&str> = previous_ranks.keys().chain(current_ranks.keys()).copied().collect::<BTreeSet<_>>().into_iter().collect();
+    names.sort_by_key(|name| current_ranks.get(name).or(previous_ranks.get(name)).map(|(rank, _)| *rank));
+
+    let mut rows: Vec<ComparisonRow> = Vec::new();
+    for name in &names {
+        let old = previous_ranks.get(name);
+        let new = current_ranks.get(name);
+        let (change, significant) = match (old, new) {
+            //positive when the contractor moved up
+            (Some((o, _)), Some((n, _))) => (format!("{:+}", *o as i64 - *n as i64), o.abs_diff(*n) >= config.rank_change),
+            (None, Some(_)) => ("Entered".to_string(), true),
+            _ => ("Left".to_string(), true),
+        };
+        rows.push(ComparisonRow {
+            report: "Contractors",
+            item: name.to_string(),
+            metric: "Rank".to_string(),
+            previous: old.map(|(rank, _)| rank.to_string()).unwrap_or_else(|| "Unranked".to_string()),
+            current: new.map(|(rank, _)| rank.to_string()).unwrap_or_else(|| "Unranked".to_string()),
+            change,
+            significant,
+        });
+    }
+
+    for name in &names {
+        if let Some((_, "High")) = current_ranks.get(name) {
+            let previous_flag = previous_ranks.get(name).map(|(_, flag)| *flag).unwrap_or("N/A");
+            if previous_flag != "High" {
+                rows.push(ComparisonRow {
+                    report: "Contractors",
+                    item: name.to_string(),
+                    metric: "RiskFlag".to_string(),
+                    previous: previous_flag.to_string(),
+                    current: "High".to_string(),
+                    change: "NewlyHigh".to_string(),
+                    significant: true,
+                });
+            }
+        }
+    }
+
+    rows
+}
+
+fn compare_regions(previous: &[ReportRow], projects: &[FloodControlProject], config: &ComparisonConfig, sorting: &SortConfig) -> Vec<ComparisonRow> {
+    let previous_scores: BTreeMap<String, Option<f64>> = previous
+        .iter()
+        .map(|row| (column(row, "Region").to_string(), parse_number(column(row, "EfficiencyScore"))))
+        .collect();
+    let current_scores: BTreeMap<String, Option<f64>> = build_regional_report(projects, &sorting.regional)
+        .into_iter()
+        .map(|stats| (stats.region, stats.efficiency_score))
+        .collect();
+
+    compare_values("Regions", "EfficiencyScore", &previous_scores, &current_scores, config.efficiency_score_change)
+}
+
+fn compare_cost_trends(previous: &[ReportRow], projects: &[FloodControlProject], config: &ComparisonConfig, sorting: &SortConfig) -> Vec<ComparisonRow> {
+    let previous_rates: BTreeMap<String, Option<f64>> = previous
+        .iter()
+        .map(|row| (format!("{} {}", column(row, "FundingYear"), column(row, "TypeOfWork")), parse_number(column(row, "OverrunRate"))))
+        .collect();
+    let current_rates: BTreeMap<String, Option<f64>> = build_cost_report(projects, &sorting.trends)
+        .into_iter()
+        .map(|stats| (format!("{} {}", stats.funding_year, stats.type_of_work), Some(stats.overrun_rate)))
+        .collect();
+
+    compare_values("CostTrends", "OverrunRate", &previous_rates, &current_rates, config.overrun_rate_change)
+}
+
+fn compare_summary(dir: &Path, projects: &[FloodControlProject], config: &ComparisonConfig) -> Result<Vec<ComparisonRow>, Box<dyn Error>> {
+    let path = dir.join("summary.json");
+    if !path.exists() {
+        println!("- {} not found, skipped", path.display());
+        return Ok(Vec::new());
+    }
+
+    let previous: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    let current = serde_json::to_value(compute_summary(projects))?;
+    let mut rows: Vec<ComparisonRow> = Vec::new();
+
+    if let Some(fields) = current.as_object() {
+        for (field, new) in fields {
+            let old = previous.get(field).cloned().unwrap_or(serde_json::Value::Null);
+            let (change, significant) = match (old.as_f64(), new.as_f64()) {
+                (Some(o), Some(n)) => {
+                    let pct = timecompare::percent_change(o, n);
+                    let significant = match pct {
+                        Some(pct) => pct.abs() >= config.summary_change_pct,
+                        None => o != n,
+                    };
+                    (format!("{}%", timecompare::format_change(pct)), significant)
+                },
+                _ => (String::new(), old != *new),
+            };
+            //strings are shown without their JSON quotes
+            let display = |value: &serde_json::Value| value.as_str().map(String::from).unwrap_or_else(|| value.to_string());
+            rows.push(ComparisonRow {
+                report: "Summary",
+                item: "summary.json".to_string(),
+                metric: field.clone(),
+                previous: display(&old),
+                current: display(new),
+                change,
+                significant,
+            });
+        }
+    }
+
+    Ok(rows)
+}
+
+fn display_comparison_report(rows: &[ComparisonRow], dir: &Path) {
+    println!("\nReport Comparison: {} vs fresh run", dir.display());
+    println!("(Significant changes only; Rank change is positive when a contractor moved up)");
+    println!("----------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<11} | {:<50} | {:<25} | {:>14} | {:>14} | {:>12} |",
+             "Report", "Item", "Metric", "Previous", "Current", "Change");
+    println!("----------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for row in rows.iter().filter(|r| r.significant) {
+        let item: String = row.item.chars().take(50).collect();
+        let previous: String = row.previous.chars().take(14).collect();
+        let current: String = row.current.chars().take(14).collect();
+
+        println!("| {:<11} | {:<50} | {:<25} | {:>14} | {:>14} | {:>12} |",
+            row.report,
+            item,
+            row.metric.chars().take(25).collect::<String>(),
+            previous,
+            current,
+            row.change
+        );
+    }
+    println!("----------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("{} significant changes out of {} compared values.", rows.iter().filter(|r| r.significant).count(), rows.len());
+}
+
+fn export_comparison_csv(rows: &[ComparisonRow], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Report", "Item", "Metric", "Previous", "Current", "Change", "Significant"])?;
+
+    //format and write data
+    for row in rows {
+        writer.write_record([
+            row.report,
+            &row.item,
+            &row.metric,
+            &row.previous,
+            &row.current,
+            &row.change,
+            if row.significant { "Yes" } else { "No" },
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+//compares the reports exported in `args[0]` (default: the data folder) with the loaded data
+pub fn compare_command(projects: &[FloodControlProject], args: &[String], config: &ComparisonConfig, sorting: &SortConfig) -> Result<(), Box<dyn Error>> {
+    let dir: PathBuf = args.first().map(PathBuf::from).unwrap_or_else(|| data_path(""));
+    let mut rows: Vec<ComparisonRow> = Vec::new();
+
+    if let Some(previous) = read_previous_csv(&dir, "report2_contractor_ranking.csv")? {
+        rows.extend(compare_contractors(&previous, projects, config, sorting));
+    }
+    if let Some(previous) = read_previous_csv(&dir, "report1_regional_summary.csv")? {
+        rows.extend(compare_regions(&previous, projects, config, sorting));
+    }
+    if let Some(previous) = read_previous_csv(&dir, "report3_annual_trends.csv")? {
+        rows.extend(compare_cost_trends(&previous, projects, config, sorting));
+    }
+    rows.extend(compare_summary(&dir, projects, config)?);
+
+    if rows.is_empty() {
+        return Err(format!("No exported reports found in {}", dir.display()).into());
+    }
+
+    display_comparison_report(&rows, &dir);
+    export_comparison_csv(&rows, "report_comparison.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_exported_numbers() {
+        assert_eq!(parse_number("1,234,567.50"), Some(1_234_567.5));
+        assert_eq!(parse_number(" -12.5 "), Some(-12.5));
+        assert_eq!(parse_number("N/A"), None);
+        assert_eq!(parse_number(""), None);
+    }
+
+    #[test]
+    fn missing_rows_are_always_significant() {
+        let previous = BTreeMap::from([("Kept".to_string(), Some(10.0)), ("Dropped".to_string(), Some(5.0)), ("Undefined".to_string(), None)]);
+        let current = BTreeMap::from([("Kept".to_string(), Some(10.5)), ("Added".to_string(), Some(1.0)), ("Undefined".to_string(), Some(2.0))]);
+        let rows = compare_values("Report", "Metric", &previous, &current, 1.0);
+
+        let row = |item: &str| rows.iter().find(|r| r.item == item).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(row("Kept").change, "0.50");
+        assert!(!row("Kept").significant);
+        assert_eq!(row("Dropped").current, "Absent");
+        assert_eq!(row("Added").previous, "Absent");
+        assert_eq!(row("Undefined").previous, "N/A");
+        assert!(row("Dropped").significant && row("Added").significant && row("Undefined").significant);
+    }
+}
@@ -0,0 +1,315 @@
+// Optional analysis settings read from data/fcp_config.json.
+// Every field has a default, so the file only needs the values being changed.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data_path;
+
+pub const CONFIG_FILENAME: &str = "fcp_config.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    pub dedup: DedupConfig,
+    pub splitting: SplittingConfig,
+    pub comparison: ComparisonConfig,
+    pub cache: CacheConfig,
+    pub duration: DurationConfig,
+    pub parsing: ParsingConfig,
+    pub columns: ColumnConfig,
+    pub validation: ValidationConfig,
+    pub sorting: SortConfig,
+    pub growth: GrowthConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuplicatePolicy {
+    KeepFirst,
+    DropAll,
+    ReportOnly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupConfig {
+    pub policy: DuplicatePolicy,
+    //minimum normalized name similarity (0-1) for the fuzzy match
+    pub fuzzy_name_similarity: f64,
+    //fuzzy matches are only reported unless this is enabled
+    pub apply_to_fuzzy: bool,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig {
+            policy: DuplicatePolicy::KeepFirst,
+            fuzzy_name_similarity: 0.9,
+            apply_to_fuzzy: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SplittingConfig {
+    //approval thresholds in pesos; a cluster is flagged when every contract is below
+    //a threshold but their combined cost reaches it
+    pub thresholds: Vec<f64>,
+    //projects of the same contractor/province/type starting within this many days of the first start are clustered
+    pub start_date_window_days: i64,
+    pub min_cluster_size: usize,
+    //minimum similarity (0-1) of two name stems, so typos and reordered words still group; numbers must match exactly
+    pub stem_similarity: f64,
+}
+
+impl Default for SplittingConfig {
+    fn default() -> Self {
+        SplittingConfig {
+            thresholds: vec![5_000_000.0, 50_000_000.0, 100_000_000.0],
+            start_date_window_days: 30,
+            min_cluster_size: 2,
+            stem_similarity: 0.9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComparisonConfig {
+    //moves of at least this many places in the contractor ranking are significant
+    pub rank_change: usize,
+    //efficiency score points (0-100 scale)
+    pub efficiency_score_change: f64,
+    //percentage points of the overrun rate
+    pub overrun_rate_change: f64,
+    //percent change of a summary.json figure
+    pub summary_change_pct: f64,
+}
+
+impl Default for ComparisonConfig {
+    fn default() -> Self {
+        ComparisonConfig {
+            rank_change: 3,
+            efficiency_score_change: 5.0,
+            overrun_rate_change: 5.0,
+            summary_change_pct: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    //reuse the binary snapshot in data/cache/ while the source file and settings are unchanged
+    pub enabled: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig { enabled: true }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DurationConfig {
+    //planned duration in calendar days per type of work, used when a row has neither
+    //a ContractDuration nor a TargetCompletionDate
+    pub standard_days: BTreeMap<String, i64>,
+    //for types of work missing from standard_days
+    pub default_days: i64,
+}
+
+impl Default for DurationConfig {
+    fn default() -> Self {
+        let standard_days = [
+            ("Construction of Drainage Structure", 210),
+            ("Construction of Flood Mitigation Structure", 240),
+            ("Construction of Slope Protection Structure", 240),
+            ("Rehabilitation of Flood Control Structure", 180),
+        ];
+        DurationConfig {
+            standard_days: standard_days.into_iter().map(|(work, days)| (work.to_string(), days)).collect(),
+            default_days: 210,
+        }
+    }
+}
+
+impl DurationConfig {
+    pub fn standard_for(&self, type_of_work: &str) -> i64 {
+        self.standard_days.get(type_of_work).copied().unwrap_or(self.default_days)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MoneyMode {
+    Strict,
+    Lenient,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParsingConfig {
+    //strict: budget and cost must be plain numbers; lenient: "₱ 1,234.50", "PHP 2.5M", "(500)" are normalized
+    pub money_mode: MoneyMode,
+    //rewrite region, province and main island names to their spelling in the bundled reference (geo.rs)
+    pub canonical_names: bool,
+}
+
+impl Default for ParsingConfig {
+    fn default() -> Self {
+        ParsingConfig { money_mode: MoneyMode::Lenient, canonical_names: true }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnConfig {
+    //expected column (e.g. "ApprovedBudgetForContract") -> header names used for it in other exports
+    pub aliases: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Info,
+    Warn,
+    Drop, //the row is left out of every report
+}
+
+//one validation rule; columns use the expected names (see schema.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "kebab-case")]
+pub enum Rule {
+    //the columns must not be blank
+    Required { columns: Vec<String>, severity: Severity },
+    //non-blank values must be numbers greater than zero
+    Positive { columns: Vec<String>, severity: Severity },
+    //non-blank values must be numbers within [min, max]
+    Range { column: String, min: f64, max: f64, severity: Severity },
+    //non-blank values must be dates, optionally with a year within [min_year, max_year]
+    Date { column: String, min_year: Option<i32>, max_year: Option<i32>, severity: Severity },
+    //ContractCost must not exceed max_ratio times ApprovedBudgetForContract
+    CostWithinBudget { max_ratio: f64, severity: Severity },
+    //a province must keep the region it first appeared under
+    RegionProvince { severity: Severity },
+    //non-blank values must match the pattern: 9 = digit, A = letter, ? = any character, * = any run
+    Pattern { column: String, pattern: String, severity: Severity },
+    //region, province, main island and provincial capital must agree with the bundled reference (geo.rs),
+    //with the capital's coordinates within max_capital_km of the reference ones
+    Reference { max_capital_km: f64, severity: Severity },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationConfig {
+    pub rules: Vec<Rule>,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        let columns = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        let range = |column: &str, min: f64, max: f64| Rule::Range { column: column.to_string(), min, max, severity: Severity::Warn };
+
+        ValidationConfig {
+            rules: vec![
+                Rule::Required { columns: columns(&["StartDate", "ApprovedBudgetForContract", "ContractCost"]), severity: Severity::Drop },
+                Rule::Required { columns: columns(&["ProjectId", "Region", "Province", "Contractor", "TypeOfWork"]), severity: Severity::Warn },
+                Rule::Positive { columns: columns(&["ApprovedBudgetForContract", "ContractCost"]), severity: Severity::Drop },
+                Rule::Date { column: "StartDate".to_string(), min_year: Some(2015), max_year: Some(2025), severity: Severity::Drop },
+                Rule::Date { column: "ActualCompletionDate".to_string(), min_year: Some(2015), max_year: Some(2030), severity: Severity::Warn },
+                //the Philippines lies within about 4.5-21.5°N and 116-127°E
+                range("ProjectLatitude", 4.5, 21.5),
+                range("ProjectLongitude", 116.0, 127.0),
+                range("ProvincialCapitalLatitude", 4.5, 21.5),
+                range("ProvincialCapitalLongitude", 116.0, 127.0),
+                Rule::CostWithinBudget { max_ratio: 1.25, severity: Severity::Warn },
+                Rule::RegionProvince { severity: Severity::Warn },
+                Rule::Pattern { column: "ProjectId".to_string(), pattern: "P*".to_string(), severity: Severity::Info },
+                //info by default: extracts often carry placeholder capitals, which would fail every row
+                Rule::Reference { max_capital_km: 25.0, severity: Severity::Info },
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+//a report column (by its CSV header) and the direction to sort it in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: String,
+    pub direction: Direction,
+}
+
+//row order of the three reports; each report's name columns are always appended as the final tie-break
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SortConfig {
+    pub regional: Vec<SortKey>,
+    pub contractors: Vec<SortKey>, //also decides which contractors make the top list
+    pub trends: Vec<SortKey>,
+}
+
+impl Default for SortConfig {
+    fn default() -> Self {
+        let key = |column: &str, direction: Direction| SortKey { column: column.to_string(), direction };
+        SortConfig {
+            regional: vec![key("EfficiencyScore", Direction::Desc)],
+            contractors: vec![key("TotalContractCost", Direction::Desc)],
+            trends: vec![key("FundingYear", Direction::Asc), key("AvgCostSavings", Direction::Desc)],
+        }
+    }
+}
+
+//a report metric compared across years, e.g. regional / MedianSavings or distributions / DelayDays.Median
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrowthMetric {
+    pub report: String,
+    pub metric: String,
+}
+
+//metrics of the growth report; see growth.rs for the report and metric names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrowthConfig {
+    pub metrics: Vec<GrowthMetric>,
+}
+
+impl Default for GrowthConfig {
+    fn default() -> Self {
+        let metric = |report: &str, metric: &str| GrowthMetric { report: report.to_string(), metric: metric.to_string() };
+        GrowthConfig {
+            metrics: vec![
+                metric("regional", "MedianSavings"),
+                metric("contractors", "TotalContractCost"),
+                metric("distributions", "DelayDays.Median"),
+                metric("timeseries", "StartedSpend"),
+            ],
+        }
+    }
+}
+
+pub fn load_config() -> Result<AnalysisConfig, Box<dyn Error>> {
+    let path = data_path(CONFIG_FILENAME);
+    if !path.exists() {
+        return Ok(AnalysisConfig::default());
+    }
+
+    let contents = std::fs::read_to_string(&path)?;
+    let config: AnalysisConfig = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid config file data/{}: {}", CONFIG_FILENAME, e))?;
+    crate::ordering::check_sorting(&config.sorting)?;
+    println!("✓ Loaded settings from data/{}", CONFIG_FILENAME);
+    Ok(config)
+}
@@ -0,0 +1,100 @@
+// Date parsing for the source extracts, which mix ISO dates, US-style MM/DD/YYYY,
+// Excel serial numbers and full datetime strings.
+
+use chrono::{Duration, NaiveDate};
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%m-%d-%Y", "%d-%b-%Y", "%b %d, %Y", "%B %d, %Y"];
+
+//serials outside this range (1900-01-01 to 9999-12-31) are not Excel dates
+const EXCEL_SERIAL_RANGE: std::ops::RangeInclusive<f64> = 1.0..=2_958_465.0;
+
+//Excel counts from 1899-12-30 once its fictional 1900-02-29 is accounted for
+fn from_excel_serial(serial: f64) -> Option<NaiveDate> {
+    if !EXCEL_SERIAL_RANGE.contains(&serial) {
+        return None;
+    }
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?;
+    epoch.checked_add_signed(Duration::days(serial.trunc() as i64))
+}
+
+fn parse_date_only(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS.iter().find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+//None for blank or unrecognized values
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Some(date) = parse_date_only(value) {
+        return Some(date);
+    }
+
+    //datetime strings: "2022-03-15 00:00:00", "2022-03-15T08:30:00Z", "3/15/2022 8:30 AM"
+    if let Some(date) = value.split(['T', ' ']).next().and_then(parse_date_only) {
+        return Some(date);
+    }
+
+    value.parse::<f64>().ok().and_then(from_excel_serial)
+}
+
+//blank for projects without a completion date
+pub fn format_date(date: Option<NaiveDate>) -> String {
+    date.map(|d| d.to_string()).unwrap_or_default()
+}
+
+//"N/A" for ongoing projects, which have no duration or delay yet
+pub fn format_days(days: Option<i64>) -> String {
+    days.map(|d| d.to_string()).unwrap_or_else(|| "N/A".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    #[test]
+    fn parses_every_date_format() {
+        for value in ["2022-03-15", "2022/03/15", "03/15/2022", "3/15/2022", "03-15-2022", "15-Mar-2022", "Mar 15, 2022", "March 15, 2022"] {
+            assert_eq!(parse_date(value), date(2022, 3, 15), "{}", value);
+        }
+    }
+
+    #[test]
+    fn keeps_the_date_of_datetime_strings() {
+        for value in ["2022-03-15 00:00:00", "2022-03-15T08:30:00Z", "3/15/2022 8:30 AM", "  2022-03-15  "] {
+            assert_eq!(parse_date(value), date(2022, 3, 15), "{}", value);
+        }
+    }
+
+    #[test]
+    fn reads_excel_serials_within_range() {
+        assert_eq!(parse_date("44635"), date(2022, 3, 15));
+        assert_eq!(parse_date("44635.75"), date(2022, 3, 15));
+        assert_eq!(parse_date("0"), None);
+        assert_eq!(parse_date("-5"), None);
+        assert_eq!(parse_date("3000000"), None);
+    }
+
+    #[test]
+    fn rejects_blank_and_invalid_dates() {
+        assert_eq!(parse_date(""), None);
+        assert_eq!(parse_date("   "), None);
+        assert_eq!(parse_date("2022-02-30"), None);
+        assert_eq!(parse_date("13/01/2022"), None);
+        assert_eq!(parse_date("soon"), None);
+    }
+
+    #[test]
+    fn formats_missing_values() {
+        assert_eq!(format_date(None), "");
+        assert_eq!(format_date(date(2022, 3, 5)), "2022-03-05");
+        assert_eq!(format_days(None), "N/A");
+        assert_eq!(format_days(Some(-3)), "-3");
+    }
+}
@@ -0,0 +1,532 @@
+// Duplicate and near-duplicate detection, run on the cleaned projects at load time.
+// Exact matches: repeated ProjectId/ContractId or identical name, location, cost and dates.
+// Fuzzy matches: similar project names in the same province and funding year.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::config::{DedupConfig, DuplicatePolicy};
+use crate::dates;
+use crate::{data_path, format_currency, snapshot, FloodControlProject};
+
+const DUPLICATES_FILENAME: &str = "report_duplicates.csv";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MatchType {
+    ExactKey,
+    IdenticalRecord,
+    FuzzyName,
+}
+
+impl MatchType {
+    fn name(self) -> &'static str {
+        match self {
+            MatchType::ExactKey => "ExactKey",
+            MatchType::IdenticalRecord => "IdenticalRecord",
+            MatchType::FuzzyName => "FuzzyName",
+        }
+    }
+}
+
+struct DuplicateGroup {
+    match_type: MatchType,
+    reasons: BTreeSet<String>,
+    members: Vec<usize>, //indices into the loaded projects, in file order
+}
+
+//also used by the contract splitting analysis to cluster related projects
+pub struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(size: usize) -> UnionFind {
+        UnionFind { parent: (0..size).collect() }
+    }
+
+    pub fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut node = i;
+        while self.parent[node] != root {
+            let next = self.parent[node];
+            self.parent[node] = root;
+            node = next;
+        }
+        root
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a != root_b {
+            //keep the earliest row as root so groups stay in file order
+            self.parent[root_a.max(root_b)] = root_a.min(root_b);
+        }
+    }
+
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut by_root: HashMap<usize, Vec<usize>> = HashMap::new();
+        for i in 0..self.parent.len() {
+            let root = self.find(i);
+            by_root.entry(root).or_default().push(i);
+        }
+        let mut groups: Vec<Vec<usize>> = by_root.into_values().filter(|g| g.len() > 1).collect();
+        groups.sort();
+        groups
+    }
+}
+
+fn normalize_name(name: &str) -> Vec<char> {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect()
+}
+
+//Levenshtein distance restricted to a diagonal band; None when it exceeds max_distance
+fn bounded_levenshtein(a: &[char], b: &[char], max_distance: usize) -> Option<usize> {
+    let (n, m) = (a.len(), b.len());
+    if n.abs_diff(m) > max_distance {
+        return None;
+    }
+
+    let limit = max_distance + 1;
+    let mut prev: Vec<usize> = (0..=m).map(|j| j.min(limit)).collect();
+    let mut curr: Vec<usize> = vec![limit; m + 1];
+
+    for i in 1..=n {
+        let low = i.saturating_sub(max_distance).max(1);
+        let high = (i + max_distance).min(m);
+
+        curr[0] = i.min(limit);
+        if low > 1 {
+            curr[low - 1] = limit;
+        }
+        if high < m {
+            curr[high + 1] = limit;
+        }
+
+        let mut row_min = curr[0];
+        for j in low..=high {
+            let substitution = prev[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            curr[j] = substitution.min(prev[j] + 1).min(curr[j - 1] + 1).min(limit);
+            row_min = row_min.min(curr[j]);
+        }
+
+        if row_min > max_distance {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    (prev[m] <= max_distance).then_some(prev[m])
+}
+
+//numbers in a name (station, phase, km post) tell distinct projects apart, so they must match exactly;
+//also used by the contract splitting analysis to compare name stems
+pub fn name_numbers(name: &[char]) -> Vec<String> {
+    name.iter()
+        .collect::<String>()
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|n| !n.is_empty())
+        .map(|n| n.to_string())
+        .collect()
+}
+
+//None below min_similarity
+pub fn name_similarity(a: &[char], b: &[char], min_similarity: f64) -> Option<f64> {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return Some(1.0);
+    }
+
+    let max_distance = ((1.0 - min_similarity) * longest as f64).floor() as usize;
+    bounded_levenshtein(a, b, max_distance).map(|d| 1.0 - d as f64 / longest as f64)
+}
+
+//(key, usable, is_id_key); rows sharing any usable key are exact duplicates
+fn exact_keys(project: &FloodControlProject) -> [(String, bool, bool); 3] {
+    let identical = format!("{}|{}|{}|{:.2}|{}|{}",
+        normalize_name(&project.project_name).iter().collect::<String>(),
+        project.region.trim().to_lowercase(),
+        project.province.trim().to_lowercase(),
+        project.contract_cost,
+        project.start_date,
+        dates::format_date(project.actual_completion_date));
+
+    [
+        (format!("ProjectId {}", project.project_id.trim()), !project.project_id.trim().is_empty(), true),
+        (format!("ContractId {}", project.contract_id.trim()), !project.contract_id.trim().is_empty(), true),
+        (format!("Identical name/location/cost/dates: {}", identical), true, false),
+    ]
+}
+
+fn find_exact_duplicates(projects: &[FloodControlProject]) -> Vec<DuplicateGroup> {
+    let mut union_find = UnionFind::new(projects.len());
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+    let mut reasons: Vec<Vec<(String, bool)>> = vec![Vec::new(); projects.len()];
+    // per row: (reason, is_id_key)
+
+    for (index, project) in projects.iter().enumerate() {
+        for (key, usable, is_id_key) in exact_keys(project) {
+            if !usable {
+                continue;
+            }
+            match first_seen.get(&key) {
+                Some(&first) => {
+                    union_find.union(first, index);
+                    let reason = if is_id_key { key.clone() } else { "Identical name/location/cost/dates".to_string() };
+                    reasons[index].push((reason, is_id_key));
+                }
+                None => {
+                    first_seen.insert(key, index);
+                }
+            }
+        }
+    }
+
+    union_find
+        .groups()
+        .into_iter()
+        .map(|members| {
+            let found: Vec<&(String, bool)> = members.iter().flat_map(|&i| &reasons[i]).collect();
+            DuplicateGroup {
+                match_type: if found.iter().any(|(_, is_id_key)| *is_id_key) { MatchType::ExactKey } else { MatchType::IdenticalRecord },
+                reasons: found.into_iter().map(|(reason, _)| reason.clone()).collect(),
+                members,
+            }
+        })
+        .collect()
+}
+
+//joins the rows into one group and records the best similarity each of them matched with
+fn link<'a>(union_find: &mut UnionFind, best_similarity: &mut HashMap<usize, f64>, rows: impl Iterator<Item = &'a usize>, similarity: f64) {
+    let mut first = None;
+    for &row in rows {
+        union_find.union(*first.get_or_insert(row), row);
+        let best = best_similarity.entry(row).or_insert(0.0);
+        *best = best.max(similarity);
+    }
+}
+
+fn find_fuzzy_duplicates(projects: &[FloodControlProject], exact: &[DuplicateGroup], min_similarity: f64) -> Vec<DuplicateGroup> {
+    //rows already grouped as exact duplicates are compared through their first row only
+    let already_grouped: HashSet<usize> = exact.iter().flat_map(|g| g.members.iter().skip(1).copied()).collect();
+
+    let names: Vec<Vec<char>> = projects.iter().map(|p| normalize_name(&p.project_name)).collect();
+
+    //names with different numbers never match, so they are bucketed apart rather than compared
+    let mut buckets: HashMap<(String, u32, Vec<String>), Vec<usize>> = HashMap::new();
+    // (province, funding_year, name numbers): row indices
+    for (index, project) in projects.iter().enumerate() {
+        if !already_grouped.contains(&index) {
+            let key = (project.province.trim().to_lowercase(), project.funding_year, name_numbers(&names[index]));
+            buckets.entry(key).or_default().push(index);
+        }
+    }
+
+    let mut union_find = UnionFind::new(projects.len());
+    let mut best_similarity: HashMap<usize, f64> = HashMap::new();
+
+    for indices in buckets.values() {
+        //rows spelling a name the same way match outright; only distinct spellings need the edit distance
+        let mut by_name: HashMap<&[char], Vec<usize>> = HashMap::new();
+        for &index in indices {
+            by_name.entry(names[index].as_slice()).or_default().push(index);
+        }
+        let spellings: Vec<(&[char], Vec<usize>)> = by_name.into_iter().collect();
+
+        for (position, (name_a, rows_a)) in spellings.iter().enumerate() {
+            if rows_a.len() > 1 {
+                link(&mut union_find, &mut best_similarity, rows_a.iter(), 1.0);
+            }
+            for (name_b, rows_b) in &spellings[position + 1..] {
+                if let Some(similarity) = name_similarity(name_a, name_b, min_similarity) {
+                    link(&mut union_find, &mut best_similarity, rows_a.iter().chain(rows_b), similarity);
+                }
+            }
+        }
+    }
+
+    union_find
+        .groups()
+        .into_iter()
+        .map(|members| {
+            let lowest = members.iter().filter_map(|i| best_similarity.get(i)).fold(1.0_f64, |acc, s| acc.min(*s));
+            DuplicateGroup {
+                match_type: MatchType::FuzzyName,
+                reasons: BTreeSet::from([format!("Similar project names (>= {:.0}% similar, same numbers), same province and funding year", lowest * 100.0)]),
+                members,
+            }
+        })
+        .collect()
+}
+
+fn rows_to_remove(groups: &[DuplicateGroup], config: &DedupConfig) -> HashSet<usize> {
+    let mut removed: HashSet<usize> = HashSet::new();
+
+    for group in groups {
+        if group.match_type == MatchType::FuzzyName && !config.apply_to_fuzzy {
+            continue;
+        }
+        match config.policy {
+            DuplicatePolicy::KeepFirst => removed.extend(group.members.iter().skip(1)),
+            DuplicatePolicy::DropAll => removed.extend(group.members.iter()),
+            DuplicatePolicy::ReportOnly => {},
+        }
+    }
+
+    removed
+}
+
+fn export_duplicates_csv(projects: &[FloodControlProject], groups: &[DuplicateGroup], removed: &HashSet<usize>) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(DUPLICATES_FILENAME))?;
+
+    //header
+    writer.write_record(["GroupId", "MatchType", "Reasons", "Action", "ProjectId", "ContractId", "ProjectName", "Province",
+        "FundingYear", "ContractCost", "StartDate", "ActualCompletionDate", "Contractor"])?;
+
+    //format and write data
+    for (group_id, group) in groups.iter().enumerate() {
+        let group_id = (group_id + 1).to_string();
+        let reasons = group.reasons.iter().cloned().collect::<Vec<_>>().join("; ");
+
+        for &index in &group.members {
+            let project = &projects[index];
+            writer.write_record([
+                group_id.as_str(),
+                group.match_type.name(),
+                &reasons,
+                if removed.contains(&index) { "Removed" } else { "Kept" },
+                &project.project_id,
+                &project.contract_id,
+                &project.project_name,
+                &project.province,
+                &project.funding_year.to_string(),
+                &format_currency(project.contract_cost),
+                &project.start_date.to_string(),
+                &dates::format_date(project.actual_completion_date),
+                &project.contractor,
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    println!("✓ Exported duplicates to data/{}", DUPLICATES_FILENAME);
+    Ok(())
+}
+
+//single-pass version of the exact-duplicate rules for the streaming loader, which never holds
+//all rows; it only matches deduplicate() when fuzzy matches are not applied and the policy keeps
+//or reports duplicates, and it sets `merged_groups` when a row joins two earlier groups, since
+//the in-memory pass would then also remove the later group's first row. Keys are kept as 64-bit
+//hashes, so each row costs a few fixed-size map entries rather than copies of its text
+pub struct StreamingDedup {
+    policy: DuplicatePolicy,
+    group_of_key: HashMap<u64, usize>,
+    rows: usize,
+    pub removed: usize,
+    pub merged_groups: bool,
+}
+
+impl StreamingDedup {
+    //None when the config needs the whole dataset (drop-all policy or applied fuzzy matches)
+    pub fn new(config: &DedupConfig) -> Option<StreamingDedup> {
+        if config.policy == DuplicatePolicy::DropAll || config.apply_to_fuzzy {
+            return None;
+        }
+        Some(StreamingDedup { policy: config.policy, group_of_key: HashMap::new(), rows: 0, removed: 0, merged_groups: false })
+    }
+
+    //false when the row duplicates an earlier kept row and should be dropped
+    pub fn keep(&mut self, project: &FloodControlProject) -> bool {
+        let index = self.rows;
+        self.rows += 1;
+
+        let keys: Vec<u64> = exact_keys(project)
+            .into_iter()
+            .filter(|(_, usable, _)| *usable)
+            .map(|(key, _, _)| snapshot::fnv1a(key.as_bytes()))
+            .collect();
+        let groups: BTreeSet<usize> = keys.iter().filter_map(|key| self.group_of_key.get(key).copied()).collect();
+        if groups.len() > 1 {
+            self.merged_groups = true;
+        }
+
+        let group = groups.first().copied().unwrap_or(index);
+        for key in keys {
+            self.group_of_key.entry(key).or_insert(group);
+        }
+
+        let duplicate = group != index;
+        if duplicate && self.policy == DuplicatePolicy::KeepFirst {
+            self.removed += 1;
+            return false;
+        }
+        true
+    }
+}
+
+pub fn deduplicate(projects: Vec<FloodControlProject>, config: &DedupConfig, export: bool) -> Result<Vec<FloodControlProject>, Box<dyn Error>> {
+    let mut groups = find_exact_duplicates(&projects);
+    let fuzzy = find_fuzzy_duplicates(&projects, &groups, config.fuzzy_name_similarity);
+    groups.extend(fuzzy);
+
+    if groups.is_empty() {
+        return Ok(projects);
+    }
+
+    let removed = rows_to_remove(&groups, config);
+    let exact_count = groups.iter().filter(|g| g.match_type != MatchType::FuzzyName).count();
+
+    println!("Duplicate check: {} exact and {} fuzzy duplicate groups, {} rows removed (policy: {:?})",
+        exact_count, groups.len() - exact_count, removed.len(), config.policy);
+    if export {
+        export_duplicates_csv(&projects, &groups, &removed)?;
+    }
+
+    Ok(projects
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !removed.contains(index))
+        .map(|(_, project)| project)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaming_check_drops_repeated_ids_under_keep_first() {
+        let mut dedup = StreamingDedup::new(&DedupConfig::default()).unwrap();
+        let first = FloodControlProject::sample("P1", "Region I", "Builder A");
+        let mut repeat = FloodControlProject::sample("P1", "Region II", "Builder B");
+        repeat.contract_id = "C-other".to_string();
+        let other = FloodControlProject::sample("P2", "Region I", "Builder A");
+
+        assert!(dedup.keep(&first));
+        assert!(!dedup.keep(&repeat));
+        assert!(dedup.keep(&other));
+        assert_eq!(dedup.removed, 1);
+        assert!(!dedup.merged_groups);
+    }
+
+    #[test]
+    fn streaming_check_flags_rows_joining_two_groups() {
+        let mut dedup = StreamingDedup::new(&DedupConfig { policy: DuplicatePolicy::ReportOnly, ..DedupConfig::default() }).unwrap();
+        let mut a = FloodControlProject::sample("P1", "Region I", "Builder A");
+        a.contract_id = "C1".to_string();
+        let mut b = FloodControlProject::sample("P2", "Region I", "Builder A");
+        b.contract_id = "C2".to_string();
+        b.project_name = "Different Work".to_string();
+        let mut bridge = FloodControlProject::sample("P1", "Region III", "Builder C");
+        bridge.contract_id = "C2".to_string();
+
+        assert!(dedup.keep(&a) && dedup.keep(&b) && dedup.keep(&bridge));
+        assert_eq!(dedup.removed, 0);
+        assert!(dedup.merged_groups);
+    }
+
+    #[test]
+    fn streaming_check_needs_the_whole_dataset_for_drop_all() {
+        assert!(StreamingDedup::new(&DedupConfig { policy: DuplicatePolicy::DropAll, ..DedupConfig::default() }).is_none());
+        assert!(StreamingDedup::new(&DedupConfig { apply_to_fuzzy: true, ..DedupConfig::default() }).is_none());
+    }
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn union_find_keeps_the_earliest_row_as_root() {
+        let mut union_find = UnionFind::new(5);
+        union_find.union(3, 1);
+        union_find.union(4, 3);
+        assert_eq!(union_find.find(4), 1);
+        assert_eq!(union_find.groups(), vec![vec![1, 3, 4]]);
+    }
+
+    #[test]
+    fn levenshtein_stops_past_the_bound() {
+        assert_eq!(bounded_levenshtein(&chars("kitten"), &chars("sitting"), 3), Some(3));
+        assert_eq!(bounded_levenshtein(&chars("kitten"), &chars("sitting"), 2), None);
+        assert_eq!(bounded_levenshtein(&chars("abc"), &chars("abcdef"), 2), None);
+        assert_eq!(bounded_levenshtein(&chars(""), &chars("ab"), 2), Some(2));
+        assert_eq!(bounded_levenshtein(&chars("same"), &chars("same"), 0), Some(0));
+    }
+
+    #[test]
+    fn similarity_uses_normalized_names() {
+        let (a, b) = (normalize_name("Flood-Control  Wall, Phase"), normalize_name("flood control wall phase"));
+        assert_eq!(name_similarity(&a, &b, 0.9), Some(1.0));
+        assert_eq!(name_similarity(&chars("abcd"), &chars("wxyz"), 0.9), None);
+        assert_eq!(name_similarity(&[], &[], 0.9), Some(1.0));
+        assert_eq!(name_numbers(&chars("km 12 station 3a")), vec!["12", "3"]);
+    }
+
+    #[test]
+    fn exact_groups_join_rows_sharing_any_key() {
+        let a = FloodControlProject::sample("P1", "Region I", "Builder A");
+        let mut b = FloodControlProject::sample("P2", "Region I", "Builder A");
+        b.contract_id = a.contract_id.clone();
+        b.project_name = "Other Work".to_string();
+        let mut c = FloodControlProject::sample("", "Region I", "Builder A");
+        c.contract_id = String::new();
+        c.project_name = "Unrelated".to_string();
+
+        let groups = find_exact_duplicates(&[a, b, c]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].members, vec![0, 1]);
+        assert_eq!(groups[0].match_type, MatchType::ExactKey);
+    }
+
+    #[test]
+    fn fuzzy_groups_need_equal_numbers() {
+        let mut projects: Vec<FloodControlProject> = ["P1", "P2", "P3"]
+            .iter()
+            .map(|id| FloodControlProject::sample(id, "Region I", "Builder A"))
+            .collect();
+        projects[0].project_name = "Construction of Flood Wall Phase 2".to_string();
+        projects[1].project_name = "Construction of Flood Walls Phase 2".to_string();
+        projects[2].project_name = "Construction of Flood Walls Phase 3".to_string();
+
+        let groups = find_fuzzy_duplicates(&projects, &[], 0.9);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].members, vec![0, 1]);
+    }
+
+    #[test]
+    fn repeated_spellings_join_the_group_of_a_near_spelling() {
+        let mut projects: Vec<FloodControlProject> = ["P1", "P2", "P3", "P4"]
+            .iter()
+            .map(|id| FloodControlProject::sample(id, "Region I", "Builder A"))
+            .collect();
+        projects[0].project_name = "Construction of Flood Wall Phase 2".to_string();
+        projects[1].project_name = "Construction of Flood Walls Phase 2".to_string();
+        projects[2].project_name = "Construction of Flood Wall Phase 2".to_string();
+        projects[3].project_name = "Desilting of Creek Phase 2".to_string();
+
+        let groups = find_fuzzy_duplicates(&projects, &[], 0.9);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].members, vec![0, 1, 2]);
+        //the group reports its weakest match, not the identical pair
+        assert!(groups[0].reasons.iter().next().unwrap().contains(">= 97% similar"));
+    }
+
+    #[test]
+    fn policies_choose_the_rows_to_remove() {
+        let group = DuplicateGroup { match_type: MatchType::ExactKey, reasons: BTreeSet::new(), members: vec![0, 2] };
+        let remove = |policy| rows_to_remove(std::slice::from_ref(&group), &DedupConfig { policy, ..DedupConfig::default() });
+        assert_eq!(remove(DuplicatePolicy::KeepFirst), HashSet::from([2]));
+        assert_eq!(remove(DuplicatePolicy::DropAll), HashSet::from([0, 2]));
+        assert!(remove(DuplicatePolicy::ReportOnly).is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+// Dataset diff between two CSV extracts: added, removed and modified projects,
+// and how the regional and contractor statistics moved between them.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::config::AnalysisConfig;
+use crate::dates;
+use crate::{
+    aggregate_contractor_stats, build_regional_report, data_path, format_currency, load_projects, numeric,
+    ContractorStats, FloodControlProject, RegionalStats, MIN_CONTRACTOR_PROJECTS,
+};
+
+//peso amounts closer than this are treated as unchanged
+const AMOUNT_TOLERANCE: f64 = 0.005;
+
+struct FieldChange {
+    change_type: &'static str,
+    key: String,
+    field: &'static str,
+    old_value: String,
+    new_value: String,
+}
+
+//projects are matched on ProjectId, or ContractId when the ProjectId is blank
+fn project_key(project: &FloodControlProject) -> String {
+    let project_id = project.project_id.trim();
+    if project_id.is_empty() {
+        format!("ContractId:{}", project.contract_id.trim())
+    } else {
+        project_id.to_string()
+    }
+}
+
+fn compare_fields(key: &str, old: &FloodControlProject, new: &FloodControlProject) -> Vec<FieldChange> {
+    let mut changes: Vec<FieldChange> = Vec::new();
+    let mut record = |field: &'static str, old_value: String, new_value: String| {
+        if old_value != new_value {
+            changes.push(FieldChange { change_type: "Modified", key: key.to_string(), field, old_value, new_value });
+        }
+    };
+
+    record("ContractId", old.contract_id.clone(), new.contract_id.clone());
+    record("ProjectName", old.project_name.clone(), new.project_name.clone());
+    record("Region", old.region.to_string(), new.region.to_string());
+    record("Province", old.province.to_string(), new.province.to_string());
+    record("TypeOfWork", old.type_of_work.to_string(), new.type_of_work.to_string());
+    record("FundingYear", old.funding_year.to_string(), new.funding_year.to_string());
+    record("Contractor", old.contractor.to_string(), new.contractor.to_string());
+    record("StartDate", old.start_date.to_string(), new.start_date.to_string());
+    record("ActualCompletionDate", dates::format_date(old.actual_completion_date), dates::format_date(new.actual_completion_date));
+
+    for (field, old_amount, new_amount) in [
+        ("ApprovedBudget", old.approved_budget, new.approved_budget),
+        ("ContractCost", old.contract_cost, new.contract_cost),
+    ] {
+        if (old_amount - new_amount).abs() > AMOUNT_TOLERANCE {
+            record(field, format_currency(old_amount), format_currency(new_amount));
+        }
+    }
+
+    changes
+}
+
+//rows sharing a key are told apart by their order in the file: the second "P1" is keyed "P1#2",
+//so it is matched with the second "P1" of the other extract instead of overwriting the first
+fn keyed_projects(projects: &[FloodControlProject]) -> (BTreeMap<String, &FloodControlProject>, BTreeSet<String>) {
+    let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+    let mut repeated: BTreeSet<String> = BTreeSet::new();
+    let mut by_key: BTreeMap<String, &FloodControlProject> = BTreeMap::new();
+
+    for project in projects {
+        let key = project_key(project);
+        let occurrence = occurrences.entry(key.clone()).or_insert(0);
+        *occurrence += 1;
+        if *occurrence == 1 {
+            by_key.insert(key, project);
+        } else {
+            by_key.insert(format!("{}#{}", key, occurrence), project);
+            repeated.insert(key);
+        }
+    }
+    (by_key, repeated)
+}
+
+fn diff_projects(old: &[FloodControlProject], new: &[FloodControlProject]) -> Vec<FieldChange> {
+    let (old_by_key, old_repeated) = keyed_projects(old);
+    let (new_by_key, new_repeated) = keyed_projects(new);
+    let keys: BTreeSet<&String> = old_by_key.keys().chain(new_by_key.keys()).collect();
+
+    for (extract, repeated) in [("Old", &old_repeated), ("New", &new_repeated)] {
+        if !repeated.is_empty() {
+            let examples: Vec<&str> = repeated.iter().take(5).map(|key| key.as_str()).collect();
+            println!("⚠ {} extract: {} project keys appear more than once (e.g. {}); repeats are matched in file order as KEY#2, KEY#3, ...",
+                extract, repeated.len(), examples.join(", "));
+        }
+    }
+
+    let mut changes: Vec<FieldChange> = Vec::new();
+    for key in keys {
+        match (old_by_key.get(key), new_by_key.get(key)) {
+            (Some(old_project), Some(new_project)) => changes.extend(compare_fields(key, old_project, new_project)),
+            (None, Some(new_project)) => changes.push(FieldChange {
+                change_type: "Added",
+                key: key.clone(),
+                field: "",
+                old_value: String::new(),
+                new_value: format!("{} | {} | {}", new_project.project_name, new_project.region, format_currency(new_project.contract_cost)),
+            }),
+            (Some(old_project), None) => changes.push(FieldChange {
+                change_type: "Removed",
+                key: key.clone(),
+                field: "",
+                old_value: format!("{} | {} | {}", old_project.project_name, old_project.region, format_currency(old_project.contract_cost)),
+                new_value: String::new(),
+            }),
+            (None, None) => {},
+        }
+    }
+
+    changes
+}
+
+fn format_delta(old: Option<f64>, new: Option<f64>) -> String {
+    match (old, new) {
+        (Some(o), Some(n)) => format!("{:.2}", n - o),
+        _ => "N/A".to_string(),
+    }
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "N/A".to_string())
+}
+
+//N/A when the row is absent on that side, "undefined" when present with an undefined score
+fn format_defined(value: Option<Option<f64>>) -> String {
+    value.map(numeric::format_defined).unwrap_or_else(|| "N/A".to_string())
+}
+
+fn export_project_changes_csv(changes: &[FieldChange], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["ChangeType", "ProjectKey", "Field", "OldValue", "NewValue"])?;
+
+    //format and write data
+    for change in changes {
+        writer.write_record([change.change_type, &change.key, change.field, &change.old_value, &change.new_value])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+fn diff_regions(old: &[RegionalStats], new: &[RegionalStats], filename: &str) -> Result<(), Box<dyn Error>> {
+    let old_by_region: BTreeMap<&str, &RegionalStats> = old.iter().map(|r| (r.region.as_str(), r)).collect();
+    let new_by_region: BTreeMap<&str, &RegionalStats> = new.iter().map(|r| (r.region.as_str(), r)).collect();
+    let regions: BTreeSet<&str> = old_by_region.keys().chain(new_by_region.keys()).copied().collect();
+
+    println!("\nRegional Statistics Movement (new - old)");
+    println!("-----------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<35} | {:>20} | {:>15} | {:>12} | {:>12} | {:>12} | {:>12} |",
+             "Region", "TotalBudgetDelta", "MedSavingsDelta", "AvgDurationΔ", "AvgDelayDelta", "HighDelay%Δ", "EfficiencyΔ");
+    println!("-----------------------------------------------------------------------------------------------------------------------------------");
+
+    let mut writer = Writer::from_path(data_path(filename))?;
+    writer.write_record(["Region", "Status", "OldTotalBudget", "NewTotalBudget", "OldMedianSavings", "NewMedianSavings",
+        "OldAvgDuration", "NewAvgDuration", "OldAvgDelay", "NewAvgDelay", "OldHighDelay%", "NewHighDelay%",
+        "OldEfficiencyScore", "NewEfficiencyScore", "EfficiencyDelta"])?;
+
+    for region in regions {
+        let old_stats = old_by_region.get(region);
+        let new_stats = new_by_region.get(region);
+        let status = match (old_stats, new_stats) {
+            (Some(_), Some(_)) => "InBoth",
+            (None, Some(_)) => "Added",
+            _ => "Removed",
+        };
+        let field = |stats: Option<&&RegionalStats>, f: fn(&RegionalStats) -> f64| stats.map(|s| f(s));
+
+        println!("| {:<35} | {:>20} | {:>15} | {:>12} | {:>12} | {:>12} | {:>12} |",
+            region,
+            format_delta(field(old_stats, |s| s.total_budget), field(new_stats, |s| s.total_budget)),
+            format_delta(field(old_stats, |s| s.median_savings), field(new_stats, |s| s.median_savings)),
+            format_delta(field(old_stats, |s| s.avg_duration), field(new_stats, |s| s.avg_duration)),
+            format_delta(field(old_stats, |s| s.avg_delay), field(new_stats, |s| s.avg_delay)),
+            format_delta(field(old_stats, |s| s.high_delay_pct), field(new_stats, |s| s.high_delay_pct)),
+            format_delta(old_stats.and_then(|s| s.efficiency_score), new_stats.and_then(|s| s.efficiency_score))
+        );
+
+        writer.write_record([
+            region,
+            status,
+            &format_optional(field(old_stats, |s| s.total_budget)),
+            &format_optional(field(new_stats, |s| s.total_budget)),
+            &format_optional(field(old_stats, |s| s.median_savings)),
+            &format_optional(field(new_stats, |s| s.median_savings)),
+            &format_optional(field(old_stats, |s| s.avg_duration)),
+            &format_optional(field(new_stats, |s| s.avg_duration)),
+            &format_optional(field(old_stats, |s| s.avg_delay)),
+            &format_optional(field(new_stats, |s| s.avg_delay)),
+            &format_optional(field(old_stats, |s| s.high_delay_pct)),
+            &format_optional(field(new_stats, |s| s.high_delay_pct)),
+            &format_defined(old_stats.map(|s| s.efficiency_score)),
+            &format_defined(new_stats.map(|s| s.efficiency_score)),
+            &format_delta(old_stats.and_then(|s| s.efficiency_score), new_stats.and_then(|s| s.efficiency_score)),
+        ])?;
+    }
+    println!("-----------------------------------------------------------------------------------------------------------------------------------");
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+fn diff_contractors(old: &[ContractorStats], new: &[ContractorStats], filename: &str) -> Result<(), Box<dyn Error>> {
+    let old_by_name: BTreeMap<&str, &ContractorStats> = old.iter().map(|c| (c.contractor.as_str(), c)).collect();
+    let new_by_name: BTreeMap<&str, &ContractorStats> = new.iter().map(|c| (c.contractor.as_str(), c)).collect();
+    let names: BTreeSet<&str> = old_by_name.keys().chain(new_by_name.keys()).copied().collect();
+
+    let mut writer = Writer::from_path(data_path(filename))?;
+    writer.write_record(["Contractor", "Status", "OldProjectCount", "NewProjectCount", "OldTotalContractCost", "NewTotalContractCost",
+        "OldReliabilityIndex", "NewReliabilityIndex", "ReliabilityDelta", "OldRiskFlag", "NewRiskFlag"])?;
+
+    let mut moved = 0;
+    for name in names {
+        let old_stats = old_by_name.get(name);
+        let new_stats = new_by_name.get(name);
+        let status = match (old_stats, new_stats) {
+            (Some(o), Some(n)) if o.project_count == n.project_count
+                && (o.total_contract_cost - n.total_contract_cost).abs() <= AMOUNT_TOLERANCE
+                && o.risk_flag == n.risk_flag => "Unchanged",
+            (Some(_), Some(_)) => "Changed",
+            (None, Some(_)) => "Added",
+            _ => "Removed",
+        };
+        if status == "Unchanged" {
+            continue;
+        }
+        moved += 1;
+
+        writer.write_record([
+            name,
+            status,
+            &old_stats.map(|s| s.project_count.to_string()).unwrap_or_default(),
+            &new_stats.map(|s| s.project_count.to_string()).unwrap_or_default(),
+            &format_optional(old_stats.map(|s| s.total_contract_cost)),
+            &format_optional(new_stats.map(|s| s.total_contract_cost)),
+            &format_defined(old_stats.map(|s| s.reliability_index)),
+            &format_defined(new_stats.map(|s| s.reliability_index)),
+            &format_delta(old_stats.and_then(|s| s.reliability_index), new_stats.and_then(|s| s.reliability_index)),
+            old_stats.map(|s| s.risk_flag.as_str()).unwrap_or(""),
+            new_stats.map(|s| s.risk_flag.as_str()).unwrap_or(""),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("{} contractors (with at least {} projects) changed, appeared or disappeared.", moved, MIN_CONTRACTOR_PROJECTS);
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn diff_command(args: &[String], config: &AnalysisConfig) -> Result<(), Box<dyn Error>> {
+    let (old_path, new_path) = match args {
+        [old, new] => (old, new),
+        _ => return Err("Usage: diff <old.csv> <new.csv>".into()),
+    };
+
+    //the load-time reports are printed for each extract but not exported, so neither overwrites the other
+    println!("Old extract: {}", old_path);
+    let old = load_projects(old_path, config, false)?;
+    println!("New extract: {}", new_path);
+    let new = load_projects(new_path, config, false)?;
+
+    let changes = diff_projects(&old, &new);
+    let count = |change_type: &str| changes.iter().filter(|c| c.change_type == change_type).count();
+    let modified_projects: BTreeSet<&str> = changes.iter().filter(|c| c.change_type == "Modified").map(|c| c.key.as_str()).collect();
+
+    println!("\nDataset Diff Summary");
+    println!("  Added projects:    {}", count("Added"));
+    println!("  Removed projects:  {}", count("Removed"));
+    println!("  Modified projects: {} ({} field changes)", modified_projects.len(), count("Modified"));
+
+    export_project_changes_csv(&changes, "diff_projects.csv")?;
+    diff_regions(&build_regional_report(&old, &config.sorting.regional), &build_regional_report(&new, &config.sorting.regional), "diff_regions.csv")?;
+    diff_contractors(
+        &aggregate_contractor_stats(&old, MIN_CONTRACTOR_PROJECTS),
+        &aggregate_contractor_stats(&new, MIN_CONTRACTOR_PROJECTS),
+        "diff_contractors.csv",
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes_of<'a>(changes: &'a [FieldChange], change_type: &str) -> Vec<&'a FieldChange> {
+        changes.iter().filter(|c| c.change_type == change_type).collect()
+    }
+
+    #[test]
+    fn repeated_keys_are_matched_in_file_order() {
+        let old = vec![
+            FloodControlProject::sample("P1", "Region I", "Builder A"),
+            FloodControlProject::sample("P1", "Region II", "Builder A"),
+        ];
+        let mut new = old.clone();
+        new[1].contractor = "Builder B".into();
+        new.push(FloodControlProject::sample("P1", "Region III", "Builder A"));
+
+        let changes = diff_projects(&old, &new);
+        let modified = changes_of(&changes, "Modified");
+        assert_eq!(modified.len(), 1);
+        assert_eq!(modified[0].key, "P1#2");
+        assert_eq!(modified[0].field, "Contractor");
+        let added = changes_of(&changes, "Added");
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].key, "P1#3");
+    }
+
+    #[test]
+    fn blank_project_ids_fall_back_to_the_contract_id() {
+        let mut old = vec![FloodControlProject::sample("", "Region I", "Builder A")];
+        old[0].contract_id = "C-9".to_string();
+        let changes = diff_projects(&old, &[]);
+        assert_eq!(changes_of(&changes, "Removed")[0].key, "ContractId:C-9");
+    }
+
+    #[test]
+    fn amounts_within_the_tolerance_are_unchanged() {
+        let old = vec![FloodControlProject::sample("P1", "Region I", "Builder A")];
+        let mut new = old.clone();
+        new[0].contract_cost += AMOUNT_TOLERANCE / 2.0;
+        assert!(diff_projects(&old, &new).is_empty());
+        new[0].contract_cost += 1.0;
+        assert_eq!(diff_projects(&old, &new).len(), 1);
+    }
+}
@@ -0,0 +1,247 @@
+// Distribution report: percentiles, spread, skewness and histograms of
+// savings, savings ratio and delay per region and per type of work.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::statistics::{self, Distribution};
+use crate::{data_path, format_currency, metric_delay_days, metric_duration_days, savings_ratio, FloodControlProject};
+
+const HISTOGRAM_BINS: usize = 10;
+const METRICS: &[&str] = &["CostSavings", "SavingsRatio%", "DurationDays", "DelayDays"];
+//as in the report's CSV header
+const STATISTICS: &[&str] = &["Count", "Min", "P10", "P25", "Median", "P75", "P90", "P99", "Max", "Mean", "StdDev", "IQR", "Skewness"];
+
+struct GroupDistribution {
+    group_by: &'static str,
+    group: String,
+    metric: &'static str,
+    values: Vec<f64>,
+    summary: Distribution,
+}
+
+#[derive(Default)]
+struct MetricValues {
+    savings: Vec<f64>,
+    savings_ratio: Vec<f64>,
+    duration: Vec<f64>,
+    delay: Vec<f64>,
+}
+
+fn collect_groups<F>(projects: &[FloodControlProject], group_by: &'static str, key: F) -> Vec<GroupDistribution>
+where
+    F: Fn(&FloodControlProject) -> &str,
+{
+    let mut groups: BTreeMap<String, MetricValues> = BTreeMap::new();
+
+    for project in projects {
+        let entry = groups.entry(key(project).to_string()).or_default();
+        entry.savings.push(project.cost_savings);
+        entry.savings_ratio.push(savings_ratio(project));
+        //ongoing projects have no delay yet, invalid timelines have no meaningful one
+        if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+            entry.duration.push(duration_days as f64);
+            entry.delay.push(delay_days as f64);
+        }
+    }
+
+    let mut results: Vec<GroupDistribution> = Vec::new();
+    for (group, values) in groups {
+        for (metric, metric_values) in [("CostSavings", values.savings), ("SavingsRatio%", values.savings_ratio), ("DurationDays", values.duration), ("DelayDays", values.delay)] {
+            results.push(GroupDistribution {
+                group_by,
+                group: group.clone(),
+                metric,
+                summary: statistics::describe(&metric_values),
+                values: metric_values,
+            });
+        }
+    }
+
+    results
+}
+
+fn statistic(summary: &Distribution, name: &str) -> f64 {
+    match name {
+        "Count" => summary.count as f64,
+        "Min" => summary.min,
+        "P10" => summary.p10,
+        "P25" => summary.p25,
+        "Median" => summary.median,
+        "P75" => summary.p75,
+        "P90" => summary.p90,
+        "P99" => summary.p99,
+        "Max" => summary.max,
+        "Mean" => summary.mean,
+        "StdDev" => summary.std_dev,
+        "IQR" => summary.iqr,
+        _ => summary.skewness,
+    }
+}
+
+//(group, year, value) of one statistic, e.g. "DelayDays.Median", for the growth report;
+//groups without values that year are left out
+pub fn metric_points(years: &[(u32, Vec<FloodControlProject>)], selector: &str) -> Result<Vec<(String, u32, f64)>, String> {
+    let (metric, name) = selector
+        .split_once('.')
+        .filter(|(metric, name)| METRICS.contains(metric) && STATISTICS.contains(name))
+        .ok_or_else(|| format!("Unknown distribution metric {} (use <metric>.<statistic>; metrics: {}; statistics: {})",
+            selector, METRICS.join(", "), STATISTICS.join(", ")))?;
+
+    let mut points: Vec<(String, u32, f64)> = Vec::new();
+    for (year, projects) in years {
+        let mut distributions = collect_groups(projects, "Region", |p| &p.region);
+        distributions.extend(collect_groups(projects, "TypeOfWork", |p| &p.type_of_work));
+        points.extend(distributions
+            .iter()
+            .filter(|dist| dist.metric == metric && dist.summary.count > 0)
+            .map(|dist| (format!("{}: {}", dist.group_by, dist.group), *year, statistic(&dist.summary, name))));
+    }
+    Ok(points)
+}
+
+fn format_metric(metric: &str, value: f64) -> String {
+    if metric == "CostSavings" { format_currency(value) } else { format!("{:.2}", value) }
+}
+
+fn display_distributions_report(distributions: &[GroupDistribution]) {
+    println!("\nDistribution Statistics by Region and Type of Work");
+    println!("(Filtered: 2021-2023 Projects)");
+    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<45} | {:<13} | {:>6} | {:>15} | {:>15} | {:>15} | {:>15} | {:>15} | {:>8} |",
+             "Group", "Metric", "Count", "P10", "Median", "P90", "Mean", "StdDev", "Skew");
+    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for dist in distributions {
+        let group: String = dist.group.chars().take(45).collect();
+
+        println!("| {:<45} | {:<13} | {:>6} | {:>15} | {:>15} | {:>15} | {:>15} | {:>15} | {:>8.2} |",
+            group,
+            dist.metric,
+            dist.summary.count,
+            format_metric(dist.metric, dist.summary.p10),
+            format_metric(dist.metric, dist.summary.median),
+            format_metric(dist.metric, dist.summary.p90),
+            format_metric(dist.metric, dist.summary.mean),
+            format_metric(dist.metric, dist.summary.std_dev),
+            dist.summary.skewness
+        );
+    }
+    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn export_distributions_csv(distributions: &[GroupDistribution], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["GroupBy", "Group", "Metric", "Count", "Min", "P10", "P25", "Median", "P75", "P90", "P99", "Max",
+        "Mean", "StdDev", "IQR", "Skewness"])?;
+
+    //format and write data
+    for dist in distributions {
+        let s = &dist.summary;
+        writer.write_record([
+            dist.group_by,
+            &dist.group,
+            dist.metric,
+            &s.count.to_string(),
+            &format!("{:.2}", s.min),
+            &format!("{:.2}", s.p10),
+            &format!("{:.2}", s.p25),
+            &format!("{:.2}", s.median),
+            &format!("{:.2}", s.p75),
+            &format!("{:.2}", s.p90),
+            &format!("{:.2}", s.p99),
+            &format!("{:.2}", s.max),
+            &format!("{:.2}", s.mean),
+            &format!("{:.2}", s.std_dev),
+            &format!("{:.2}", s.iqr),
+            &format!("{:.4}", s.skewness),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+fn export_histograms_csv(distributions: &[GroupDistribution], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["GroupBy", "Group", "Metric", "Bin", "Lower", "Upper", "Count"])?;
+
+    //format and write data
+    for dist in distributions {
+        for (index, bin) in statistics::histogram(&dist.values, HISTOGRAM_BINS).iter().enumerate() {
+            writer.write_record([
+                dist.group_by,
+                &dist.group,
+                dist.metric,
+                &(index + 1).to_string(),
+                &format!("{:.2}", bin.lower),
+                &format!("{:.2}", bin.upper),
+                &bin.count.to_string(),
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn distributions_report(projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    let mut distributions = collect_groups(projects, "Region", |p| &p.region);
+    distributions.extend(collect_groups(projects, "TypeOfWork", |p| &p.type_of_work));
+
+    display_distributions_report(&distributions);
+    export_distributions_csv(&distributions, "report_distributions.csv")?;
+    export_histograms_csv(&distributions, "report_distribution_histograms.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_and_duration_leave_out_ongoing_projects() {
+        let mut ongoing = FloodControlProject::sample("P1", "Region I", "Builder A");
+        ongoing.actual_completion_date = None;
+        ongoing.duration_days = None;
+        ongoing.delay_days = None;
+        ongoing.status = crate::status::ProjectStatus::Ongoing;
+        let completed = FloodControlProject::sample("P2", "Region I", "Builder A");
+        let other = FloodControlProject::sample("P3", "Region II", "Builder A");
+
+        let groups = collect_groups(&[ongoing, completed, other], "Region", |p| &p.region);
+        let counts: Vec<(&str, &str, usize)> = groups.iter().map(|g| (g.group.as_str(), g.metric, g.values.len())).collect();
+        assert_eq!(&counts[..4], &[
+            ("Region I", "CostSavings", 2),
+            ("Region I", "SavingsRatio%", 2),
+            ("Region I", "DurationDays", 1),
+            ("Region I", "DelayDays", 1),
+        ]);
+        assert_eq!(groups.len(), 8);
+        assert_eq!(format_metric("CostSavings", 1234.5), format_currency(1234.5));
+        assert_eq!(format_metric("DelayDays", 1.0 / 3.0), "0.33");
+    }
+
+    #[test]
+    fn metric_points_pick_one_statistic_per_group_and_year() {
+        let mut later = FloodControlProject::sample("P2", "Region I", "Builder A");
+        later.funding_year = 2023;
+        later.delay_days = Some(30);
+        let years = vec![(2022, vec![FloodControlProject::sample("P1", "Region I", "Builder A")]), (2023, vec![later])];
+
+        let points = metric_points(&years, "DelayDays.Median").unwrap();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0], ("Region: Region I".to_string(), 2022, 0.0));
+        assert_eq!(points[2], ("Region: Region I".to_string(), 2023, 30.0));
+        assert!(metric_points(&years, "DelayDays").is_err());
+        assert!(metric_points(&years, "Delay.Median").is_err());
+    }
+}
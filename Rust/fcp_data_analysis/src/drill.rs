@@ -0,0 +1,185 @@
+// Drill-down: lists the projects behind a single aggregate row of report 1, 2 or 3.
+// Members are the rows carrying the row's grouping key (region, contractor, or funding year
+// and type of work), the same key the aggregation groups by, so the listed rows always
+// match the row's project count, even when a ProjectId appears more than once.
+
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::dates;
+use crate::{
+    aggregate_contractor_stats, aggregate_cost_stats, aggregate_regional_stats, data_path,
+    format_currency, numeric, FloodControlProject,
+};
+
+pub fn drill_command(projects: &[FloodControlProject], args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = "Usage: drill <region|contractor|type> <name>   (e.g. drill type \"Construction of Drainage Structure 2022\")";
+    let kind = args.first().ok_or(usage)?;
+    let name = args[1..].join(" ");
+    if name.trim().is_empty() {
+        return Err(usage.into());
+    }
+
+    let (label, members) = match kind.as_str() {
+        "region" => drill_region(projects, &name)?,
+        "contractor" => drill_contractor(projects, &name)?,
+        "type" => drill_type(projects, &name)?,
+        other => return Err(format!("Unknown drill kind '{}'. {}", other, usage).into()),
+    };
+
+    display_drill_report(&label, &members);
+    export_drill_csv(&members, &format!("drill_{}_{}.csv", kind, slugify(&name)))?;
+    Ok(())
+}
+
+type Drill<'a> = (String, Vec<&'a FloodControlProject>);
+
+fn drill_region<'a>(projects: &'a [FloodControlProject], name: &str) -> Result<Drill<'a>, Box<dyn Error>> {
+    let stats = aggregate_regional_stats(projects)
+        .into_iter()
+        .find(|r| r.region.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("No region named '{}'", name))?;
+
+    let label = format!("Region: {} ({}) | MedianSavings {} | AvgDelay {:.2} | HighDelay% {:.2}",
+        stats.region, stats.main_island, format_currency(stats.median_savings), stats.avg_delay, stats.high_delay_pct);
+    let members = projects.iter().filter(|p| p.region.as_ref() == stats.region).collect();
+    Ok((label, members))
+}
+
+fn drill_contractor<'a>(projects: &'a [FloodControlProject], name: &str) -> Result<Drill<'a>, Box<dyn Error>> {
+    //no minimum project count here, any contractor can be inspected
+    let stats = aggregate_contractor_stats(projects, 0)
+        .into_iter()
+        .find(|c| c.contractor.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("No contractor named '{}'", name))?;
+
+    let label = format!("Contractor: {} | Projects {} | TotalSavings {} | Reliability {} | Risk {}",
+        stats.contractor, stats.project_count, format_currency(stats.total_cost_savings), numeric::format_defined(stats.reliability_index), stats.risk_flag);
+    let members = projects.iter().filter(|p| p.contractor.as_ref() == stats.contractor).collect();
+    Ok((label, members))
+}
+
+fn drill_type<'a>(projects: &'a [FloodControlProject], name: &str) -> Result<Drill<'a>, Box<dyn Error>> {
+    //the funding year is the last word, e.g. "Construction of Drainage Structure 2022"
+    let (type_of_work, year) = name
+        .trim()
+        .rsplit_once(' ')
+        .and_then(|(t, y)| y.parse::<u32>().ok().map(|y| (t.trim(), y)))
+        .ok_or_else(|| format!("Expected '<type of work> <funding year>', got '{}'", name))?;
+
+    let stats = aggregate_cost_stats(projects)
+        .into_iter()
+        .find(|c| c.funding_year == year && c.type_of_work.eq_ignore_ascii_case(type_of_work))
+        .ok_or_else(|| format!("No '{}' projects funded in {}", type_of_work, year))?;
+
+    let label = format!("TypeOfWork: {} ({}) | Projects {} | AvgSavings {} | OverrunRate {:.2}",
+        stats.type_of_work, stats.funding_year, stats.total_projects, format_currency(stats.avg_cost_savings), stats.overrun_rate);
+    let members = projects
+        .iter()
+        .filter(|p| p.funding_year == stats.funding_year && p.type_of_work.as_ref() == stats.type_of_work)
+        .collect();
+    Ok((label, members))
+}
+
+fn display_drill_report(label: &str, members: &[&FloodControlProject]) {
+    println!("\nDrill-down: {}", label);
+    println!("({} contributing projects)", members.len());
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<15} | {:>6} | {:<20} | {:>17} | {:>15} | {:>8} | {:>8} | {:<40} |",
+             "ProjectId", "Year", "Province", "ContractCost", "CostSavings", "Duration", "Delay", "ProjectName");
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for project in members {
+        let name: String = project.project_name.chars().take(40).collect();
+
+        println!("| {:<15} | {:>6} | {:<20} | {:>17} | {:>15} | {:>8} | {:>8} | {:<40} |",
+            project.project_id,
+            project.funding_year,
+            project.province,
+            format_currency(project.contract_cost),
+            format_currency(project.cost_savings),
+            dates::format_days(project.duration_days),
+            dates::format_days(project.delay_days),
+            name
+        );
+    }
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn export_drill_csv(members: &[&FloodControlProject], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["ProjectId", "ContractId", "ProjectName", "Region", "Province", "TypeOfWork", "FundingYear", "Contractor",
+        "ApprovedBudget", "ContractCost", "CostSavings", "StartDate", "ActualCompletionDate", "PlannedDurationDays", "DurationDays", "CompletionDelayDays", "Status"])?;
+
+    //format and write data
+    for project in members {
+        writer.write_record([
+            project.project_id.as_str(),
+            &project.contract_id,
+            &project.project_name,
+            &project.region,
+            &project.province,
+            &project.type_of_work,
+            &project.funding_year.to_string(),
+            &project.contractor,
+            &format_currency(project.approved_budget),
+            &format_currency(project.contract_cost),
+            &format_currency(project.cost_savings),
+            &project.start_date.to_string(),
+            &dates::format_date(project.actual_completion_date),
+            &project.planned_duration_days.to_string(),
+            &dates::format_days(project.duration_days),
+            &dates::format_days(project.delay_days),
+            project.status.label(),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_project_ids_stay_in_their_own_group() {
+        let projects = vec![
+            FloodControlProject::sample("P1", "Region I", "Builder A"),
+            FloodControlProject::sample("P1", "Region II", "Builder A"),
+            FloodControlProject::sample("P2", "Region I", "Builder B"),
+        ];
+
+        let (_, members) = drill_region(&projects, "region i").unwrap();
+        assert_eq!(members.len(), 2);
+        assert!(members.iter().all(|p| p.region.as_ref() == "Region I"));
+
+        let (_, members) = drill_contractor(&projects, "Builder A").unwrap();
+        assert_eq!(members.len(), 2);
+    }
+
+    #[test]
+    fn type_drill_needs_a_funding_year() {
+        let projects = vec![FloodControlProject::sample("P1", "Region I", "Builder A")];
+        assert!(drill_type(&projects, "Construction of Flood Mitigation Structure").is_err());
+        let (_, members) = drill_type(&projects, "Construction of Flood Mitigation Structure 2022").unwrap();
+        assert_eq!(members.len(), 1);
+    }
+
+    #[test]
+    fn slugifies_names() {
+        assert_eq!(slugify("Region IV-A (CALABARZON)"), "region_iv_a_calabarzon");
+    }
+}
@@ -0,0 +1,447 @@
+// Bundled Philippine geographic reference: regions with their main island, and provinces
+// with their region and provincial capital. Used to rewrite region, province and island
+// names to one spelling before grouping, and by the reference validation rule to check
+// the main island, the province's region and the provincial capital against it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::sync::LazyLock;
+
+use csv::Writer;
+
+use crate::schema::header_key;
+use crate::{data_path, RawFloodControlProject};
+
+const ISLANDS: [&str; 3] = ["Luzon", "Visayas", "Mindanao"];
+
+//(region, main island, other names it is written as)
+const REGIONS: &[(&str, &str, &[&str])] = &[
+    ("National Capital Region", "Luzon", &["NCR", "Metro Manila"]),
+    ("Cordillera Administrative Region", "Luzon", &["CAR", "Cordillera"]),
+    ("Region I", "Luzon", &["Region 1", "Ilocos Region", "Ilocos"]),
+    ("Region II", "Luzon", &["Region 2", "Cagayan Valley"]),
+    ("Region III", "Luzon", &["Region 3", "Central Luzon"]),
+    ("Region IV-A", "Luzon", &["Region 4-A", "Region 4A", "CALABARZON"]),
+    ("Region IV-B", "Luzon", &["Region 4-B", "Region 4B", "MIMAROPA", "Southwestern Tagalog Region"]),
+    ("Region V", "Luzon", &["Region 5", "Bicol Region", "Bicol"]),
+    ("Region VI", "Visayas", &["Region 6", "Western Visayas"]),
+    ("Region VII", "Visayas", &["Region 7", "Central Visayas"]),
+    ("Region VIII", "Visayas", &["Region 8", "Eastern Visayas"]),
+    ("Region IX", "Mindanao", &["Region 9", "Zamboanga Peninsula"]),
+    ("Region X", "Mindanao", &["Region 10", "Northern Mindanao"]),
+    ("Region XI", "Mindanao", &["Region 11", "Davao Region"]),
+    ("Region XII", "Mindanao", &["Region 12", "SOCCSKSARGEN"]),
+    ("Region XIII", "Mindanao", &["Region 13", "Caraga"]),
+    ("Bangsamoro Autonomous Region in Muslim Mindanao", "Mindanao", &["BARMM", "ARMM", "Bangsamoro"]),
+];
+
+//(province, region, provincial capital, capital latitude, capital longitude)
+type Province = (&'static str, &'static str, &'static str, f64, f64);
+
+//Metro Manila stands in for NCR's cities
+const PROVINCES: &[Province] = &[
+    ("Metro Manila", "National Capital Region", "Manila", 14.5995, 120.9842),
+    ("Abra", "Cordillera Administrative Region", "Bangued", 17.5960, 120.6180),
+    ("Apayao", "Cordillera Administrative Region", "Kabugao", 18.0230, 121.1840),
+    ("Benguet", "Cordillera Administrative Region", "La Trinidad", 16.4550, 120.5880),
+    ("Ifugao", "Cordillera Administrative Region", "Lagawe", 16.8250, 121.1250),
+    ("Kalinga", "Cordillera Administrative Region", "Tabuk", 17.4130, 121.4440),
+    ("Mountain Province", "Cordillera Administrative Region", "Bontoc", 17.0890, 120.9770),
+    ("Ilocos Norte", "Region I", "Laoag", 18.1980, 120.5940),
+    ("Ilocos Sur", "Region I", "Vigan", 17.5750, 120.3870),
+    ("La Union", "Region I", "San Fernando", 16.6160, 120.3190),
+    ("Pangasinan", "Region I", "Lingayen", 16.0210, 120.2310),
+    ("Batanes", "Region II", "Basco", 20.4480, 121.9700),
+    ("Cagayan", "Region II", "Tuguegarao", 17.6130, 121.7270),
+    ("Isabela", "Region II", "Ilagan", 17.1480, 121.8890),
+    ("Nueva Vizcaya", "Region II", "Bayombong", 16.4840, 121.1490),
+    ("Quirino", "Region II", "Cabarroguis", 16.5110, 121.5220),
+    ("Aurora", "Region III", "Baler", 15.7590, 121.5620),
+    ("Bataan", "Region III", "Balanga", 14.6760, 120.5360),
+    ("Bulacan", "Region III", "Malolos", 14.8430, 120.8110),
+    ("Nueva Ecija", "Region III", "Palayan", 15.5420, 121.0840),
+    ("Pampanga", "Region III", "San Fernando", 15.0340, 120.6840),
+    ("Tarlac", "Region III", "Tarlac", 15.4800, 120.5970),
+    ("Zambales", "Region III", "Iba", 15.3270, 119.9780),
+    ("Batangas", "Region IV-A", "Batangas", 13.7560, 121.0580),
+    ("Cavite", "Region IV-A", "Trece Martires", 14.2820, 120.8670),
+    ("Laguna", "Region IV-A", "Santa Cruz", 14.2780, 121.4160),
+    ("Quezon", "Region IV-A", "Lucena", 13.9310, 121.6170),
+    ("Rizal", "Region IV-A", "Antipolo", 14.5860, 121.1760),
+    ("Marinduque", "Region IV-B", "Boac", 13.4470, 121.8400),
+    ("Occidental Mindoro", "Region IV-B", "Mamburao", 13.2230, 120.5960),
+    ("Oriental Mindoro", "Region IV-B", "Calapan", 13.4110, 121.1800),
+    ("Palawan", "Region IV-B", "Puerto Princesa", 9.7400, 118.7350),
+    ("Romblon", "Region IV-B", "Romblon", 12.5750, 122.2710),
+    ("Albay", "Region V", "Legazpi", 13.1390, 123.7340),
+    ("Camarines Norte", "Region V", "Daet", 14.1120, 122.9550),
+    ("Camarines Sur", "Region V", "Pili", 13.5830, 123.2750),
+    ("Catanduanes", "Region V", "Virac", 13.5810, 124.2310),
+    ("Masbate", "Region V", "Masbate", 12.3700, 123.6200),
+    ("Sorsogon", "Region V", "Sorsogon", 12.9740, 124.0060),
+    ("Aklan", "Region VI", "Kalibo", 11.7070, 122.3670),
+    ("Antique", "Region VI", "San Jose de Buenavista", 10.7440, 121.9410),
+    ("Capiz", "Region VI", "Roxas", 11.5850, 122.7510),
+    ("Guimaras", "Region VI", "Jordan", 10.6580, 122.5960),
+    ("Iloilo", "Region VI", "Iloilo", 10.7200, 122.5620),
+    ("Negros Occidental", "Region VI", "Bacolod", 10.6760, 122.9510),
+    ("Bohol", "Region VII", "Tagbilaran", 9.6500, 123.8530),
+    ("Cebu", "Region VII", "Cebu", 10.3160, 123.8910),
+    ("Negros Oriental", "Region VII", "Dumaguete", 9.3070, 123.3080),
+    ("Siquijor", "Region VII", "Siquijor", 9.2140, 123.5150),
+    ("Biliran", "Region VIII", "Naval", 11.5610, 124.3960),
+    ("Eastern Samar", "Region VIII", "Borongan", 11.6080, 125.4320),
+    ("Leyte", "Region VIII", "Tacloban", 11.2440, 125.0040),
+    ("Northern Samar", "Region VIII", "Catarman", 12.4990, 124.6380),
+    ("Samar", "Region VIII", "Catbalogan", 11.7750, 124.8860),
+    ("Southern Leyte", "Region VIII", "Maasin", 10.1330, 124.8450),
+    ("Zamboanga del Norte", "Region IX", "Dipolog", 8.5890, 123.3410),
+    ("Zamboanga del Sur", "Region IX", "Pagadian", 7.8260, 123.4370),
+    ("Zamboanga Sibugay", "Region IX", "Ipil", 7.7820, 122.5870),
+    ("Bukidnon", "Region X", "Malaybalay", 8.1540, 125.1270),
+    ("Camiguin", "Region X", "Mambajao", 9.2510, 124.7170),
+    ("Lanao del Norte", "Region X", "Tubod", 8.0550, 123.7930),
+    ("Misamis Occidental", "Region X", "Oroquieta", 8.4860, 123.8050),
+    ("Misamis Oriental", "Region X", "Cagayan de Oro", 8.4540, 124.6320),
+    ("Davao de Oro", "Region XI", "Nabunturan", 7.6010, 125.9660),
+    ("Davao del Norte", "Region XI", "Tagum", 7.4480, 125.8080),
+    ("Davao del Sur", "Region XI", "Digos", 6.7500, 125.3570),
+    ("Davao Occidental", "Region XI", "Malita", 6.4050, 125.6110),
+    ("Davao Oriental", "Region XI", "Mati", 6.9550, 126.2170),
+    ("Cotabato", "Region XII", "Kidapawan", 7.0080, 125.0890),
+    ("Sarangani", "Region XII", "Alabel", 6.1020, 125.2900),
+    ("South Cotabato", "Region XII", "Koronadal", 6.5030, 124.8470),
+    ("Sultan Kudarat", "Region XII", "Isulan", 6.6290, 124.6050),
+    ("Agusan del Norte", "Region XIII", "Cabadbaran", 9.1240, 125.5350),
+    ("Agusan del Sur", "Region XIII", "Prosperidad", 8.6060, 125.9150),
+    ("Dinagat Islands", "Region XIII", "San Jose", 10.0080, 125.5720),
+    ("Surigao del Norte", "Region XIII", "Surigao", 9.7890, 125.4950),
+    ("Surigao del Sur", "Region XIII", "Tandag", 9.0790, 126.1980),
+    ("Basilan", "Bangsamoro Autonomous Region in Muslim Mindanao", "Lamitan", 6.6500, 122.1330),
+    ("Lanao del Sur", "Bangsamoro Autonomous Region in Muslim Mindanao", "Marawi", 8.0000, 124.2920),
+    ("Maguindanao", "Bangsamoro Autonomous Region in Muslim Mindanao", "Buluan", 6.7170, 124.7850),
+    ("Maguindanao del Norte", "Bangsamoro Autonomous Region in Muslim Mindanao", "Datu Odin Sinsuat", 7.1900, 124.1800),
+    ("Maguindanao del Sur", "Bangsamoro Autonomous Region in Muslim Mindanao", "Buluan", 6.7170, 124.7850),
+    ("Sulu", "Bangsamoro Autonomous Region in Muslim Mindanao", "Jolo", 6.0520, 121.0020),
+    ("Tawi-Tawi", "Bangsamoro Autonomous Region in Muslim Mindanao", "Bongao", 5.0290, 119.7730),
+];
+
+//(former or informal province name, province)
+const PROVINCE_ALIASES: &[(&str, &str)] = &[
+    ("NCR", "Metro Manila"),
+    ("Mt. Province", "Mountain Province"),
+    ("Compostela Valley", "Davao de Oro"),
+    ("Western Samar", "Samar"),
+    ("North Cotabato", "Cotabato"),
+    ("Dinagat", "Dinagat Islands"),
+];
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+//a value that differs from the reference; rows with the same one are counted together
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Mismatch {
+    pub column: &'static str,
+    pub group: String, //the region or province the value was checked for
+    pub found: String,
+    pub expected: String,
+}
+
+//one comparison against the reference; columns are scored as in the scorecard
+pub struct Check {
+    pub column: &'static str,
+    pub mismatch: Option<Mismatch>,
+}
+
+//names rewritten to the reference spelling and values that disagree with it, with row counts
+#[derive(Debug, Default)]
+pub struct ReferenceCounts {
+    renamed: BTreeMap<(&'static str, String, &'static str), usize>,
+    mismatches: BTreeMap<Mismatch, usize>,
+}
+
+impl ReferenceCounts {
+    pub fn record(&mut self, mismatch: Mismatch) {
+        *self.mismatches.entry(mismatch).or_default() += 1;
+    }
+
+    pub fn merge(&mut self, other: ReferenceCounts) {
+        for (key, count) in other.renamed {
+            *self.renamed.entry(key).or_default() += count;
+        }
+        for (key, count) in other.mismatches {
+            *self.mismatches.entry(key).or_default() += count;
+        }
+    }
+}
+
+//every reference name and alias by its exact spelling and by its header_key, so a lookup
+//of a name already in the reference spelling needs no key at all, and any other one key and one hash
+struct Index {
+    regions: HashMap<String, (&'static str, &'static str)>, //(region, main island)
+    provinces: HashMap<String, &'static Province>,
+    islands: HashMap<String, &'static str>,
+    capital_coordinates: HashMap<&'static str, String>, //"lat, lon (capital)" by province, as mismatches show it
+}
+
+fn insert_spellings<T: Copy>(map: &mut HashMap<String, T>, name: &str, value: T) {
+    map.insert(name.to_string(), value);
+    map.insert(header_key(name), value);
+}
+
+static INDEX: LazyLock<Index> = LazyLock::new(|| {
+    let mut regions = HashMap::new();
+    for (region, island, aliases) in REGIONS {
+        for name in std::iter::once(region).chain(aliases.iter()) {
+            insert_spellings(&mut regions, name, (*region, *island));
+        }
+    }
+
+    let mut provinces = HashMap::new();
+    for province in PROVINCES {
+        insert_spellings(&mut provinces, province.0, province);
+    }
+    for (alias, name) in PROVINCE_ALIASES {
+        if let Some(province) = provinces.get(*name).copied() {
+            insert_spellings(&mut provinces, alias, province);
+        }
+    }
+
+    let mut islands = HashMap::new();
+    for island in ISLANDS {
+        insert_spellings(&mut islands, island, island);
+    }
+    let capital_coordinates = PROVINCES
+        .iter()
+        .map(|(province, _, capital, latitude, longitude)| (*province, format!("{}, {} ({})", latitude, longitude, capital)))
+        .collect();
+    Index { regions, provinces, islands, capital_coordinates }
+});
+
+fn lookup<T: Copy>(map: &HashMap<String, T>, name: &str) -> Option<T> {
+    map.get(name).or_else(|| map.get(&header_key(name))).copied()
+}
+
+fn find_region(name: &str) -> Option<(&'static str, &'static str)> {
+    lookup(&INDEX.regions, name)
+}
+
+//the main island a reference region is on
+pub fn region_island(name: &str) -> Option<&'static str> {
+    find_region(name).map(|(_, island)| island)
+}
+
+fn find_province(name: &str) -> Option<&'static Province> {
+    lookup(&INDEX.provinces, name)
+}
+
+fn find_island(name: &str) -> Option<&'static str> {
+    lookup(&INDEX.islands, name)
+}
+
+fn capital_words(name: &str) -> impl Iterator<Item = &str> {
+    name.split_whitespace().filter(|word| !word.eq_ignore_ascii_case("city") && !word.eq_ignore_ascii_case("of"))
+}
+
+//"City of San Fernando", "San Fernando City" and "san fernando" are the same capital; compared without allocating
+fn same_capital(a: &str, b: &str) -> bool {
+    let (mut a, mut b) = (capital_words(a), capital_words(b));
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return true,
+            (Some(x), Some(y)) if x.chars().flat_map(char::to_lowercase).eq(y.chars().flat_map(char::to_lowercase)) => {},
+            _ => return false,
+        }
+    }
+}
+
+//great-circle distance
+fn distance_km((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+fn rename(field: &mut String, column: &'static str, canonical: Option<&'static str>, counts: &mut ReferenceCounts) {
+    if let Some(canonical) = canonical && field.as_str() != canonical {
+        *counts.renamed.entry((column, field.clone(), canonical)).or_default() += 1;
+        *field = canonical.to_string();
+    }
+}
+
+//rewrites the region, province and main island to their reference spelling; unknown names are kept
+pub fn canonicalize(raw: &mut RawFloodControlProject, counts: &mut ReferenceCounts) {
+    let region = find_region(&raw.region).map(|(region, _)| region);
+    rename(&mut raw.region, "Region", region, counts);
+    let province = find_province(&raw.province).map(|(province, _, _, _, _)| *province);
+    rename(&mut raw.province, "Province", province, counts);
+    let island = find_island(&raw.main_island);
+    rename(&mut raw.main_island, "MainIsland", island, counts);
+}
+
+//compares the row's location columns with the reference; blank values are left to the required rule
+pub fn check(raw: &RawFloodControlProject, max_capital_km: f64) -> Vec<Check> {
+    let mut checks: Vec<Check> = Vec::new();
+    let mismatch = |column: &'static str, group: &str, found: String, expected: String| Check {
+        column,
+        mismatch: Some(Mismatch { column, group: group.to_string(), found, expected }),
+    };
+    let (region_name, province_name) = (raw.region.trim(), raw.province.trim());
+
+    let region = if region_name.is_empty() { None } else { find_region(region_name) };
+    match region {
+        None if !region_name.is_empty() => {
+            checks.push(mismatch("Region", region_name, region_name.to_string(), "a reference region".to_string()));
+        },
+        None => {},
+        Some((_, island)) => {
+            let main_island = raw.main_island.trim();
+            if !main_island.is_empty() {
+                checks.push(match find_island(main_island) {
+                    Some(found) if found == island => Check { column: "MainIsland", mismatch: None },
+                    _ => mismatch("MainIsland", region_name, main_island.to_string(), island.to_string()),
+                });
+            }
+        },
+    }
+
+    if province_name.is_empty() {
+        return checks;
+    }
+    let Some(&(province, province_region, capital, latitude, longitude)) = find_province(province_name) else {
+        checks.push(mismatch("Province", province_name, province_name.to_string(), "a reference province".to_string()));
+        return checks;
+    };
+    checks.push(match region {
+        Some((found, _)) if found != province_region => mismatch("Province", province_name, found.to_string(), province_region.to_string()),
+        _ => Check { column: "Province", mismatch: None },
+    });
+
+    let capital_name = raw.provincial_capital.trim();
+    if !capital_name.is_empty() {
+        checks.push(if same_capital(capital_name, capital) {
+            Check { column: "ProvincialCapital", mismatch: None }
+        } else {
+            mismatch("ProvincialCapital", province_name, capital_name.to_string(), capital.to_string())
+        });
+    }
+
+    let (lat_text, lon_text) = (raw.provincial_capital_latitude.trim(), raw.provincial_capital_longitude.trim());
+    if let (Ok(lat), Ok(lon)) = (lat_text.parse::<f64>(), lon_text.parse::<f64>()) {
+        checks.push(if distance_km((lat, lon), (latitude, longitude)) <= max_capital_km {
+            Check { column: "ProvincialCapitalCoordinates", mismatch: None }
+        } else {
+            mismatch("ProvincialCapitalCoordinates", province_name, [lat_text, lon_text].join(", "), INDEX.capital_coordinates[province].clone())
+        });
+    }
+
+    checks
+}
+
+pub fn display_reference(counts: &ReferenceCounts) {
+    let renamed_rows: usize = counts.renamed.values().sum();
+    println!("\nReference Check (bundled Philippine regions, provinces and capitals)");
+    println!("Names rewritten to the reference spelling: {} values in {} distinct spellings", renamed_rows, counts.renamed.len());
+    for ((column, from, to), rows) in counts.renamed.iter().take(20) {
+        println!("- {}: {:?} → {} ({} rows)", column, from, to, rows);
+    }
+    if counts.mismatches.is_empty() {
+        println!("No mismatches against the reference.");
+        return;
+    }
+
+    //most frequent first; ties keep the column/group order
+    let mut mismatches: Vec<(&Mismatch, &usize)> = counts.mismatches.iter().collect();
+    mismatches.sort_by(|a, b| b.1.cmp(a.1));
+
+    println!("------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<28} | {:<25} | {:<30} | {:<34} | {:>8} |", "Column", "Group", "Found", "Expected", "Rows");
+    println!("------------------------------------------------------------------------------------------------------------------------------------------");
+    for (mismatch, rows) in mismatches.iter().take(20) {
+        let clip = |text: &str, width: usize| text.chars().take(width).collect::<String>();
+        println!("| {:<28} | {:<25} | {:<30} | {:<34} | {:>8} |",
+            mismatch.column, clip(&mismatch.group, 25), clip(&mismatch.found, 30), clip(&mismatch.expected, 34), rows);
+    }
+    println!("------------------------------------------------------------------------------------------------------------------------------------------");
+    if mismatches.len() > 20 {
+        println!("({} more mismatches in the CSV)", mismatches.len() - 20);
+    }
+}
+
+pub fn export_reference_csv(counts: &ReferenceCounts, filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Kind", "Column", "Group", "Found", "Expected", "Rows"])?;
+
+    //format and write data
+    for ((column, from, to), rows) in &counts.renamed {
+        writer.write_record(["Renamed", column, "", from, to, &rows.to_string()])?;
+    }
+    for (mismatch, rows) in &counts.mismatches {
+        writer.write_record(["Mismatch", mismatch.column, &mismatch.group, &mismatch.found, &mismatch.expected, &rows.to_string()])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mismatched(checks: &[Check]) -> Vec<(&'static str, &str)> {
+        checks.iter().filter_map(|c| c.mismatch.as_ref().map(|m| (c.column, m.expected.as_str()))).collect()
+    }
+
+    #[test]
+    fn canonicalizes_aliases_and_spellings() {
+        let mut raw = RawFloodControlProject::sample(&[("Region", "region 3"), ("Province", "Compostela Valley"), ("MainIsland", "LUZON")]);
+        let mut counts = ReferenceCounts::default();
+        canonicalize(&mut raw, &mut counts);
+        assert_eq!((raw.region.as_str(), raw.province.as_str(), raw.main_island.as_str()), ("Region III", "Davao de Oro", "Luzon"));
+        assert_eq!(counts.renamed.len(), 3);
+
+        let mut unknown = RawFloodControlProject::sample(&[("Region", "Region XX")]);
+        canonicalize(&mut unknown, &mut counts);
+        assert_eq!(unknown.region, "Region XX");
+    }
+
+    #[test]
+    fn matches_capitals_written_differently() {
+        assert!(same_capital("City of San Fernando", "San Fernando City"));
+        assert!(same_capital("  san   fernando ", "San Fernando"));
+        assert!(!same_capital("San Fernando", "San Fernando de Dilao"));
+        assert!(!same_capital("Capital", "Laoag"));
+    }
+
+    #[test]
+    fn measures_great_circle_distance() {
+        assert_eq!(distance_km((14.5995, 120.9842), (14.5995, 120.9842)), 0.0);
+        //one degree of latitude is about 111 km
+        assert!((distance_km((10.0, 120.0), (11.0, 120.0)) - 111.19).abs() < 0.1);
+    }
+
+    #[test]
+    fn checks_location_columns_against_the_reference() {
+        let raw = RawFloodControlProject::sample(&[
+            ("Region", "Region I"), ("Province", "Pampanga"), ("MainIsland", "Visayas"),
+            ("ProvincialCapital", "City of San Fernando"), ("ProvincialCapitalLatitude", "14.0"), ("ProvincialCapitalLongitude", "120.6"),
+        ]);
+        let checks = check(&raw, 25.0);
+        assert_eq!(checks.len(), 4);
+        assert_eq!(mismatched(&checks), vec![("MainIsland", "Luzon"), ("Province", "Region III"), ("ProvincialCapitalCoordinates", "15.034, 120.684 (San Fernando)")]);
+    }
+
+    #[test]
+    fn unknown_names_are_mismatches_and_blanks_are_skipped() {
+        let unknown = RawFloodControlProject::sample(&[("Region", "Region XX"), ("Province", "Atlantis")]);
+        assert_eq!(mismatched(&check(&unknown, 25.0)), vec![("Region", "a reference region"), ("Province", "a reference province")]);
+
+        let blank = RawFloodControlProject::sample(&[("Region", ""), ("Province", "")]);
+        assert!(check(&blank, 25.0).is_empty());
+    }
+}
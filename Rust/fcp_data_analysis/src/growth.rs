@@ -0,0 +1,203 @@
+// Year-over-year change and CAGR of any report metric (see timecompare.rs), for the metrics
+// listed in the growth section of data/fcp_config.json. The regional, contractor and
+// distribution reports are rebuilt per funding year; the cost trends report already has a
+// row per funding year; time series quarters are compared with the same quarter of earlier
+// calendar years.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::config::{GrowthConfig, GrowthMetric, CONFIG_FILENAME};
+use crate::ordering::{SortValue, Sortable};
+use crate::timecompare::{self, YearComparison};
+use crate::{
+    aggregate_contractor_stats, aggregate_cost_stats, aggregate_regional_stats, data_path, distributions, timeseries,
+    FloodControlProject,
+};
+
+//metric names: the report's CSV columns; distributions take <metric>.<statistic>, e.g. DelayDays.Median
+pub const REPORTS: &[&str] = &["regional", "contractors", "trends", "distributions", "timeseries"];
+
+struct GrowthRow {
+    report: String,
+    metric: String,
+    group: String,
+    year: u32,
+    value: f64,
+    comparison: YearComparison,
+}
+
+fn by_funding_year(projects: &[FloodControlProject]) -> Vec<(u32, Vec<FloodControlProject>)> {
+    let mut years: BTreeMap<u32, Vec<FloodControlProject>> = BTreeMap::new();
+    for project in projects {
+        years.entry(project.funding_year).or_default().push(project.clone());
+    }
+    years.into_iter().collect()
+}
+
+//the report's rows for each funding year
+fn per_year<T>(projects: &[FloodControlProject], build: impl Fn(&[FloodControlProject]) -> Vec<T>) -> Vec<(u32, T)> {
+    by_funding_year(projects)
+        .iter()
+        .flat_map(|(year, year_projects)| build(year_projects).into_iter().map(move |row| (*year, row)))
+        .collect()
+}
+
+//(group, year, value) of a number column; undefined values are left out, so the next year compares with an earlier one
+fn row_points<T: Sortable>(report: &str, column: &str, rows: &[(u32, T)], group: fn(&T) -> &str) -> Result<Vec<(String, u32, f64)>, String> {
+    if !T::COLUMNS.contains(&column) {
+        return Err(format!("Unknown {} metric {} (metrics: {})", report, column, T::COLUMNS.join(", ")));
+    }
+
+    let mut points: Vec<(String, u32, f64)> = Vec::new();
+    for (year, row) in rows {
+        match row.sort_value(column) {
+            SortValue::Number(value) => points.push((group(row).to_string(), *year, value)),
+            SortValue::Missing => {},
+            SortValue::Text(_) => return Err(format!("{} {} is not a number", report, column)),
+        }
+    }
+    Ok(points)
+}
+
+fn metric_points(selector: &GrowthMetric, projects: &[FloodControlProject]) -> Result<Vec<(String, u32, f64)>, String> {
+    let (report, metric) = (selector.report.as_str(), selector.metric.as_str());
+    match report {
+        "regional" => row_points(report, metric, &per_year(projects, aggregate_regional_stats), |r| &r.region),
+        "contractors" => row_points(report, metric, &per_year(projects, |p| aggregate_contractor_stats(p, 1)), |c| &c.contractor),
+        "trends" => {
+            let rows: Vec<_> = aggregate_cost_stats(projects).into_iter().map(|c| (c.funding_year, c)).collect();
+            row_points(report, metric, &rows, |c| &c.type_of_work)
+        },
+        "distributions" => distributions::metric_points(&by_funding_year(projects), metric),
+        "timeseries" => timeseries::quarter_points(projects, metric),
+        other => Err(format!("Unknown report {} (reports: {})", other, REPORTS.join(", "))),
+    }
+}
+
+fn compare_metric(selector: &GrowthMetric, projects: &[FloodControlProject]) -> Result<Vec<GrowthRow>, String> {
+    let mut points = metric_points(selector, projects)?;
+    points.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+    let comparisons = timecompare::compare_by_year(&points);
+
+    Ok(points
+        .into_iter()
+        .zip(comparisons)
+        .map(|((group, year, value), comparison)| GrowthRow {
+            report: selector.report.clone(),
+            metric: selector.metric.clone(),
+            group,
+            year,
+            value,
+            comparison,
+        })
+        .collect())
+}
+
+fn display_growth_report(selector: &GrowthMetric, rows: &[GrowthRow]) {
+    println!("\nYear-over-Year Growth: {} ({} report)", selector.metric, selector.report);
+    println!("(YoY against the nearest earlier year with data, CAGR since the group's first year)");
+    println!("---------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<45} | {:>6} | {:>20} | {:>8} | {:>10} | {:>9} | {:>10} |",
+             "Group", "Year", "Value", "BaseYear", "YoY%", "CAGRSince", "CAGR%");
+    println!("---------------------------------------------------------------------------------------------------------------------------");
+
+    for row in rows {
+        let group: String = row.group.chars().take(45).collect();
+
+        println!("| {:<45} | {:>6} | {:>20.2} | {:>8} | {:>10} | {:>9} | {:>10} |",
+            group,
+            row.year,
+            row.value,
+            timecompare::format_year(row.comparison.base_year),
+            timecompare::format_change(row.comparison.yoy_change),
+            timecompare::format_year(row.comparison.cagr_since),
+            timecompare::format_change(row.comparison.cagr)
+        );
+    }
+    println!("---------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn export_growth_csv(rows: &[GrowthRow], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Report", "Metric", "Group", "Year", "Value", "YoYBaseYear", "YoYChange", "CAGRSince", "CAGR"])?;
+
+    //format and write data
+    for row in rows {
+        writer.write_record([
+            &row.report,
+            &row.metric,
+            &row.group,
+            &row.year.to_string(),
+            &format!("{:.2}", row.value),
+            &timecompare::format_year(row.comparison.base_year),
+            &timecompare::format_change(row.comparison.yoy_change),
+            &timecompare::format_year(row.comparison.cagr_since),
+            &timecompare::format_change(row.comparison.cagr),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn growth_report(projects: &[FloodControlProject], config: &GrowthConfig) -> Result<(), Box<dyn Error>> {
+    let mut rows: Vec<GrowthRow> = Vec::new();
+    for selector in &config.metrics {
+        let metric_rows = compare_metric(selector, projects)
+            .map_err(|e| format!("{} in growth.metrics of data/{}", e, CONFIG_FILENAME))?;
+        display_growth_report(selector, &metric_rows);
+        rows.extend(metric_rows);
+    }
+
+    export_growth_csv(&rows, "report_growth.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selector(report: &str, metric: &str) -> GrowthMetric {
+        GrowthMetric { report: report.to_string(), metric: metric.to_string() }
+    }
+
+    #[test]
+    fn compares_a_regional_metric_across_funding_years() {
+        //Region I has no 2022 projects, so 2023 is compared with 2021
+        let year = |id: &str, region: &str, funding_year: u32, budget: f64| {
+            let mut project = FloodControlProject::sample(id, region, "Builder A");
+            project.funding_year = funding_year;
+            project.approved_budget = budget;
+            project
+        };
+        let projects = [
+            year("P1", "Region I", 2021, 1_000_000.0),
+            year("P2", "Region II", 2022, 1_000_000.0),
+            year("P3", "Region I", 2023, 1_210_000.0),
+        ];
+
+        let rows = compare_metric(&selector("regional", "TotalBudget"), &projects).unwrap();
+        let latest = rows.iter().find(|r| r.group == "Region I" && r.year == 2023).unwrap();
+        assert_eq!(latest.comparison.base_year, Some(2021));
+        assert!((latest.comparison.yoy_change.unwrap() - 21.0).abs() < 1e-9);
+        assert!((latest.comparison.cagr.unwrap() - 10.0).abs() < 1e-9);
+        assert!(rows.iter().find(|r| r.group == "Region II").unwrap().comparison.yoy_change.is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_reports_and_text_or_unknown_columns() {
+        let projects = [FloodControlProject::sample("P1", "Region I", "Builder A")];
+        assert!(compare_metric(&selector("regions", "TotalBudget"), &projects).is_err());
+        assert!(compare_metric(&selector("regional", "Budget"), &projects).is_err());
+        assert!(compare_metric(&selector("contractors", "RiskFlag"), &projects).is_err());
+        for metric in GrowthConfig::default().metrics {
+            assert!(compare_metric(&metric, &projects).is_ok(), "{} {}", metric.report, metric.metric);
+        }
+    }
+}
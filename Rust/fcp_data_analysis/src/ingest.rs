@@ -0,0 +1,309 @@
+// CSV ingestion: records are read in chunks and deserialized and cleaned on several
+// threads, then handed on in file order. read_csv_file collects the chunks into a Vec;
+// the stream command feeds them straight into the report accumulators instead, so only
+// one chunk of rows is held at a time. Memory still grows with the row count, by about
+// 100 bytes per row: the regional medians keep every row's savings (the median is exact,
+// matching the in-memory reports) and the duplicate check keeps hashes of each row's keys.
+// The validation rules run on the worker threads too, except the region/province rule,
+// which runs as the chunks come back in order.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::thread;
+use std::time::Instant;
+
+use csv::{ReaderBuilder, StringRecord};
+
+use crate::config::{AnalysisConfig, MoneyMode, Severity};
+use crate::dedup::StreamingDedup;
+use crate::intern::Interner;
+use crate::money::MoneyCounts;
+use crate::schema::{self, Layout};
+use crate::geo;
+use crate::validation::{self, ProvinceRegions, Rules, Scorecard, ValidatedRow};
+use crate::{
+    clean_project, contractor_performance_report, cost_overrun_report, generate_all_reports, generate_summary_json,
+    rank_contractor_stats, rank_cost_stats, rank_regional_stats, read_csv_file, regional_flood_mitgation_report,
+    ContractorAccumulator, CostAccumulator, FloodControlProject, RawFloodControlProject, RegionalAccumulator,
+    SummaryAccumulator, FUNDING_YEARS, MIN_CONTRACTOR_PROJECTS, TOP_CONTRACTORS,
+};
+
+//records read before a chunk is split across the worker threads
+const CHUNK_ROWS: usize = 16_384;
+//bytes inspected to detect the encoding and delimiter
+const SNIFF_BYTES: usize = 64 * 1024;
+
+pub struct IngestCounts {
+    pub total_rows: usize,
+    pub kept_rows: usize,
+    pub money: MoneyCounts,
+    pub layout: Layout,
+    pub quality: Scorecard,
+}
+
+impl IngestCounts {
+    //prints the load summary and the data quality scorecard, and exports the scorecard when asked
+    pub fn report(&self, config: &AnalysisConfig, export: bool) -> Result<(), Box<dyn Error>> {
+        if let Some(layout) = self.layout.describe() {
+            println!("Detected layout: {}", layout);
+        }
+        println!("Processing data...({} rows loaded, {} filtered for 2021-2023)", self.total_rows, self.kept_rows);
+        match config.parsing.money_mode {
+            MoneyMode::Lenient if self.money.normalized > 0 => {
+                println!("Money values: {} normalized (separators, currency, suffixes or parentheses)", self.money.normalized);
+            },
+            MoneyMode::Strict if self.money.rejected_strict > 0 => {
+                println!("Money values: {} rejected by strict mode; set parsing.money_mode to \"lenient\" to accept them",
+                    self.money.rejected_strict);
+            },
+            _ => {},
+        }
+        println!("Validation: {} rows dropped, {} kept with warnings, {} with notes only (rules in validation.rules)",
+            self.quality.rows_with(Severity::Drop),
+            self.quality.rows_with(Severity::Warn),
+            self.quality.rows_with(Severity::Info));
+        validation::display_scorecard(&self.quality);
+        geo::display_reference(&self.quality.reference);
+        if export {
+            validation::export_scorecard_csv(&self.quality, "report_data_quality.csv")?;
+            geo::export_reference_csv(&self.quality.reference, "report_reference_mismatches.csv")?;
+        }
+        Ok(())
+    }
+}
+
+//a worker's share of a chunk: rows in the analysis window, in file order
+#[derive(Default)]
+struct CleanedRows {
+    rows: Vec<ValidatedRow>,
+    money: MoneyCounts,
+    quality: Scorecard,
+}
+
+fn worker_count() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+fn clean_records(headers: &StringRecord, records: &[StringRecord], config: &AnalysisConfig, rules: &Rules) -> CleanedRows {
+    let mut cleaned = CleanedRows::default();
+    //names are interned straight from the raw fields, one copy per distinct name on this worker
+    let mut names = Interner::default();
+    for record in records {
+        let Ok(mut raw) = record.deserialize::<RawFloodControlProject>(Some(headers)) else { continue };
+        if !raw.funding_year.is_some_and(|year| FUNDING_YEARS.contains(&year)) {
+            continue;
+        }
+        if config.parsing.canonical_names {
+            geo::canonicalize(&mut raw, &mut cleaned.quality.reference);
+        }
+        let issues = validation::check_row(&raw, rules, &mut cleaned.quality);
+        let (region, province) = (names.intern(&raw.region), names.intern(&raw.province));
+        //cleaned even when a rule drops the row, so the money counts cover every row in the window
+        let project = clean_project(raw, config, &mut cleaned.money, &mut names);
+        cleaned.rows.push(ValidatedRow { region, province, issues, project });
+    }
+    cleaned
+}
+
+//cleans one chunk on all workers; the slices are joined back in their original order
+fn clean_chunk(headers: &StringRecord, chunk: &[StringRecord], config: &AnalysisConfig, rules: &Rules, workers: usize) -> CleanedRows {
+    if workers <= 1 || chunk.len() < workers * 2 {
+        return clean_records(headers, chunk, config, rules);
+    }
+
+    let slice_len = chunk.len().div_ceil(workers);
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunk
+            .chunks(slice_len)
+            .map(|slice| scope.spawn(move || clean_records(headers, slice, config, rules)))
+            .collect();
+
+        let mut cleaned = CleanedRows { rows: Vec::with_capacity(chunk.len()), ..CleanedRows::default() };
+        for handle in handles {
+            //a panicking worker would otherwise lose its whole slice; fail the load the same way instead
+            let slice = handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            cleaned.rows.extend(slice.rows);
+            cleaned.money.merge(slice.money);
+            cleaned.quality.merge(slice.quality);
+        }
+        cleaned
+    })
+}
+
+//parses and cleans every row of `input`, passing the cleaned projects to `sink` chunk by chunk in file order;
+//rows that fail to parse are counted but skipped, as before, and names are interned across all chunks.
+//The encoding, delimiter and header names are detected first; missing required columns are an error.
+//Rows a validation rule drops are left out; the rest are scored in IngestCounts::quality
+pub fn parse_projects<R: BufRead>(input: R, config: &AnalysisConfig, mut sink: impl FnMut(Vec<FloodControlProject>)) -> Result<IngestCounts, Box<dyn Error>> {
+    let rules = validation::check_rules(config)?;
+    let (input, encoding, delimiter) = schema::open_source(input)?;
+    let mut reader = ReaderBuilder::new().delimiter(delimiter).from_reader(input);
+    let (headers, mapped) = schema::map_headers(reader.headers()?, &config.columns)?;
+    let workers = worker_count();
+    let mut interner = Interner::default();
+    let mut province_regions = ProvinceRegions::default();
+
+    let layout = Layout { encoding, delimiter, mapped };
+    let mut counts = IngestCounts { total_rows: 0, kept_rows: 0, money: MoneyCounts::default(), layout, quality: Scorecard::default() };
+    let mut chunk: Vec<StringRecord> = Vec::with_capacity(CHUNK_ROWS);
+    let mut record = StringRecord::new();
+
+    loop {
+        let more = match reader.read_record(&mut record) {
+            Ok(more) => more,
+            Err(e) if e.is_io_error() => return Err(e.into()),
+            Err(_) => {
+                counts.total_rows += 1;
+                continue;
+            },
+        };
+        if more {
+            counts.total_rows += 1;
+            chunk.push(record.clone());
+        }
+
+        if chunk.len() == CHUNK_ROWS || (!more && !chunk.is_empty()) {
+            let slice = clean_chunk(&headers, &chunk, config, &rules, workers);
+            counts.money.merge(slice.money);
+            counts.quality.merge(slice.quality);
+
+            let mut cleaned: Vec<FloodControlProject> = Vec::with_capacity(slice.rows.len());
+            for mut row in slice.rows {
+                province_regions.check(&mut row, &config.validation, &mut counts.quality);
+                if let Some(mut project) = counts.quality.accept(row) {
+                    interner.intern_project(&mut project);
+                    cleaned.push(project);
+                }
+            }
+            counts.kept_rows += cleaned.len();
+            sink(cleaned);
+            chunk.clear();
+        }
+        if !more {
+            break;
+        }
+    }
+
+    Ok(counts)
+}
+
+//the three reports and summary of menu option [2], computed without keeping the rows
+pub fn stream_command(args: &[String], default_path: &Path, config: &AnalysisConfig) -> Result<(), Box<dyn Error>> {
+    let path = args.first().map(Path::new).unwrap_or(default_path);
+
+    let Some(mut dedup) = StreamingDedup::new(&config.dedup) else {
+        println!("⚠ The duplicate policy needs the whole dataset; loading it into memory instead.");
+        return run_in_memory(path, config);
+    };
+
+    let started = Instant::now();
+    let bytes = std::fs::metadata(path)?.len();
+    let mut regional: RegionalAccumulator = RegionalAccumulator::default();
+    let mut contractors: ContractorAccumulator = ContractorAccumulator::default();
+    let mut costs: CostAccumulator = CostAccumulator::default();
+    let mut summary: SummaryAccumulator = SummaryAccumulator::default();
+
+    let counts = parse_projects(BufReader::with_capacity(SNIFF_BYTES, File::open(path)?), config, |chunk| {
+        for project in chunk.iter().filter(|p| dedup.keep(p)) {
+            regional.add(project);
+            contractors.add(project);
+            costs.add(project);
+            summary.add(project);
+        }
+    })?;
+
+    if dedup.merged_groups {
+        println!("⚠ Duplicate groups overlap in a way only the in-memory check resolves; loading it into memory instead.");
+        return run_in_memory(path, config);
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    counts.report(config, true)?;
+    if dedup.removed > 0 {
+        println!("Duplicate check: {} rows removed (policy: {:?}); run the in-memory load for the duplicates report",
+            dedup.removed, config.dedup.policy);
+    }
+    println!("Streamed {} rows ({:.1} MB) in {:.2}s: {:.0} rows/s, {:.1} MB/s on {} threads",
+        counts.total_rows,
+        bytes as f64 / 1_048_576.0,
+        elapsed,
+        counts.total_rows as f64 / elapsed.max(f64::EPSILON),
+        bytes as f64 / 1_048_576.0 / elapsed.max(f64::EPSILON),
+        worker_count());
+
+    let sorting = &config.sorting;
+    regional_flood_mitgation_report(&rank_regional_stats(regional.finish(), &sorting.regional))?;
+    contractor_performance_report(&rank_contractor_stats(contractors.finish(MIN_CONTRACTOR_PROJECTS), TOP_CONTRACTORS, &sorting.contractors), sorting)?;
+    cost_overrun_report(&rank_cost_stats(costs.finish(), &sorting.trends))?;
+    generate_summary_json(&summary.finish())?;
+    Ok(())
+}
+
+fn run_in_memory(path: &Path, config: &AnalysisConfig) -> Result<(), Box<dyn Error>> {
+    let projects = read_csv_file(path, config)?;
+    generate_all_reports(&projects, &config.sorting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "MainIsland,Region,Province,ProjectId,ProjectName,TypeOfWork,FundingYear,ContractId,\
+        ApprovedBudgetForContract,ContractCost,ActualCompletionDate,Contractor,StartDate";
+
+    fn row(id: usize, year: u32) -> String {
+        format!("Luzon,Region I,Pangasinan,P{id},Flood Wall {id},Construction of Flood Mitigation Structure,{year},C{id},\
+            1000000,950000,2022-11-06,Builder A,2022-03-01")
+    }
+
+    fn parse(csv: &str) -> Result<(Vec<FloodControlProject>, IngestCounts), Box<dyn Error>> {
+        let mut projects: Vec<FloodControlProject> = Vec::new();
+        let counts = parse_projects(csv.as_bytes(), &AnalysisConfig::default(), |chunk| projects.extend(chunk))?;
+        Ok((projects, counts))
+    }
+
+    #[test]
+    fn keeps_rows_in_file_order_and_counts_every_row() {
+        let rows: Vec<String> = (0..50).map(|id| row(id, if id % 10 == 0 { 2019 } else { 2022 })).collect();
+        let (projects, counts) = parse(&format!("{}\n{}\n", HEADER, rows.join("\n"))).unwrap();
+
+        let expected: Vec<String> = (0..50).filter(|id| id % 10 != 0).map(|id| format!("P{}", id)).collect();
+        assert_eq!(projects.iter().map(|p| p.project_id.clone()).collect::<Vec<_>>(), expected);
+        assert_eq!((counts.total_rows, counts.kept_rows), (50, 45));
+    }
+
+    #[test]
+    fn workers_return_slices_in_order() {
+        let csv = format!("{}\n{}\n", HEADER, (0..40).map(|id| row(id, 2022)).collect::<Vec<_>>().join("\n"));
+        let mut reader = ReaderBuilder::new().from_reader(csv.as_bytes());
+        let headers = reader.headers().unwrap().clone();
+        let records: Vec<StringRecord> = reader.records().map(Result::unwrap).collect();
+        let config = AnalysisConfig::default();
+        let rules = validation::check_rules(&config).unwrap();
+
+        let ids = |cleaned: CleanedRows| -> Vec<String> {
+            cleaned.rows.into_iter().map(|row| row.project.unwrap().project_id).collect()
+        };
+        assert_eq!(ids(clean_chunk(&headers, &records, &config, &rules, 3)), ids(clean_chunk(&headers, &records, &config, &rules, 1)));
+    }
+
+    #[test]
+    fn drops_rows_failing_a_drop_rule() {
+        let (projects, counts) = parse(&format!("{}\n{}\n{}\n", HEADER, row(1, 2022), row(2, 2022).replace("950000", ""))).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(counts.quality.rows_with(Severity::Drop), 1);
+    }
+
+    #[test]
+    fn reads_other_layouts_and_rejects_missing_columns() {
+        let semicolons = format!("\u{feff}{}\n{}\n", HEADER.replace(',', ";"), row(1, 2022).replace(',', ";"));
+        let (projects, counts) = parse(&semicolons).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(counts.layout.describe().unwrap(), "UTF-8 with BOM; ';'-delimited");
+
+        let without_start = HEADER.trim_end_matches(",StartDate");
+        assert!(parse(&format!("{}\n", without_start)).is_err());
+    }
+}
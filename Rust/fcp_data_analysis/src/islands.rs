@@ -0,0 +1,253 @@
+// Main island as a grouping dimension: a rollup per island (Luzon, Visayas, Mindanao)
+// and a per-region check listing every MainIsland value seen for the region, against
+// the island the bundled reference places the region on.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::intern::Name;
+use crate::{
+    data_path, dominant_island, format_currency, geo, metric_delay_days, metric_duration_days, statistics, FloodControlProject,
+    HIGH_DELAY_DAYS,
+};
+
+#[derive(Default)]
+struct IslandTotals {
+    regions: BTreeSet<Name>,
+    projects: u32,
+    total_budget: f64,
+    total_contract_cost: f64,
+    savings: Vec<f64>,
+    total_duration_days: i64,
+    total_delay_days: i64,
+    high_delay: u32,
+    completed: u32, //projects whose duration and delay count toward the averages
+}
+
+impl IslandTotals {
+    fn average(&self, total: i64) -> f64 {
+        if self.completed > 0 { total as f64 / self.completed as f64 } else { 0.0 }
+    }
+
+    fn high_delay_pct(&self) -> f64 {
+        if self.completed > 0 { (self.high_delay as f64 / self.completed as f64) * 100.0 } else { 0.0 }
+    }
+}
+
+struct RegionIslands {
+    region: Name,
+    islands: BTreeMap<Name, u32>,
+    reference: Option<&'static str>,
+}
+
+impl RegionIslands {
+    fn projects(&self) -> u32 {
+        self.islands.values().sum()
+    }
+
+    fn status(&self) -> &'static str {
+        let dominant = dominant_island(&self.islands);
+        if self.islands.len() > 1 {
+            "Mixed"
+        } else if self.reference.is_some_and(|island| island != dominant) {
+            "DiffersFromReference"
+        } else if self.reference.is_none() {
+            "NotInReference"
+        } else {
+            "Consistent"
+        }
+    }
+}
+
+fn aggregate_islands(projects: &[FloodControlProject]) -> BTreeMap<Name, IslandTotals> {
+    let mut islands: BTreeMap<Name, IslandTotals> = BTreeMap::new();
+    for project in projects {
+        let totals = islands.entry(project.main_island.clone()).or_default();
+        totals.regions.insert(project.region.clone());
+        totals.projects += 1;
+        totals.total_budget += project.approved_budget;
+        totals.total_contract_cost += project.contract_cost;
+        totals.savings.push(project.cost_savings);
+        if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+            totals.total_duration_days += duration_days;
+            totals.total_delay_days += delay_days;
+            if delay_days > HIGH_DELAY_DAYS {
+                totals.high_delay += 1;
+            }
+            totals.completed += 1;
+        }
+    }
+    islands
+}
+
+fn aggregate_region_islands(projects: &[FloodControlProject]) -> Vec<RegionIslands> {
+    let mut regions: BTreeMap<Name, BTreeMap<Name, u32>> = BTreeMap::new();
+    for project in projects {
+        *regions.entry(project.region.clone()).or_default().entry(project.main_island.clone()).or_insert(0) += 1;
+    }
+
+    regions
+        .into_iter()
+        .map(|(region, islands)| RegionIslands { reference: geo::region_island(&region), region, islands })
+        .collect()
+}
+
+fn display_island_rollup(islands: &BTreeMap<Name, IslandTotals>) {
+    println!("\nMain Island Rollup");
+    println!("(Filtered: 2021-2023 Projects; AvgDuration, AvgDelay and HighDelay% over completed projects)");
+    println!("-----------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<10} | {:>7} | {:>8} | {:>20} | {:>20} | {:>15} | {:>11} | {:>9} | {:>10} |",
+             "MainIsland", "Regions", "Projects", "TotalBudget", "TotalContractCost", "MedianSavings", "AvgDuration", "AvgDelay", "HighDelay%");
+    println!("-----------------------------------------------------------------------------------------------------------------------------------------");
+
+    for (island, totals) in islands {
+        println!("| {:<10} | {:>7} | {:>8} | {:>20} | {:>20} | {:>15} | {:>11.2} | {:>9.2} | {:>10.2} |",
+            island,
+            totals.regions.len(),
+            totals.projects,
+            format_currency(totals.total_budget),
+            format_currency(totals.total_contract_cost),
+            format_currency(statistics::median(&totals.savings)),
+            totals.average(totals.total_duration_days),
+            totals.average(totals.total_delay_days),
+            totals.high_delay_pct()
+        );
+    }
+    println!("-----------------------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn display_region_islands(regions: &[RegionIslands]) {
+    println!("\nMainIsland Values by Region");
+    println!("----------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<35} | {:<35} | {:<10} | {:<25} |", "Region", "Islands Seen (Projects)", "Reference", "Status");
+    println!("----------------------------------------------------------------------------------------------------------------------");
+
+    for region in regions {
+        let seen: Vec<String> = region.islands.iter().map(|(island, count)| format!("{} ({})", island, count)).collect();
+        let name: String = region.region.chars().take(35).collect();
+        println!("| {:<35} | {:<35} | {:<10} | {:<25} |",
+            name, seen.join(", "), region.reference.unwrap_or("-"), region.status());
+    }
+    println!("----------------------------------------------------------------------------------------------------------------------");
+
+    let flagged = regions.iter().filter(|r| r.status() != "Consistent").count();
+    println!("{} of {} regions are flagged: more than one island, an island other than the reference one, or not in the reference",
+        flagged, regions.len());
+}
+
+fn export_island_rollup_csv(islands: &BTreeMap<Name, IslandTotals>, filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["MainIsland", "Regions", "Projects", "TotalBudget", "TotalContractCost", "TotalSavings",
+        "MedianSavings", "AvgDuration", "AvgDelay", "HighDelay%"])?;
+
+    //format and write data
+    for (island, totals) in islands {
+        writer.write_record([
+            island.as_ref(),
+            &totals.regions.len().to_string(),
+            &totals.projects.to_string(),
+            &format_currency(totals.total_budget),
+            &format_currency(totals.total_contract_cost),
+            &format_currency(totals.savings.iter().sum()),
+            &format_currency(statistics::median(&totals.savings)),
+            &format!("{:.2}", totals.average(totals.total_duration_days)),
+            &format!("{:.2}", totals.average(totals.total_delay_days)),
+            &format!("{:.2}", totals.high_delay_pct()),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+fn export_region_islands_csv(regions: &[RegionIslands], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Region", "MainIsland", "Projects", "Share%", "Dominant", "ReferenceIsland", "Status"])?;
+
+    //format and write data, one row per island value seen in the region
+    for region in regions {
+        let total = region.projects();
+        let dominant = dominant_island(&region.islands);
+        for (island, count) in &region.islands {
+            writer.write_record([
+                region.region.as_ref(),
+                island.as_ref(),
+                &count.to_string(),
+                &format!("{:.2}", (*count as f64 / total as f64) * 100.0),
+                if island.as_ref() == dominant { "Yes" } else { "No" },
+                region.reference.unwrap_or(""),
+                region.status(),
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn islands_report(projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    let islands = aggregate_islands(projects);
+    let regions = aggregate_region_islands(projects);
+
+    display_island_rollup(&islands);
+    display_region_islands(&regions);
+    export_island_rollup_csv(&islands, "report_islands.csv")?;
+    export_region_islands_csv(&regions, "report_region_islands.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(islands: &[(&str, u32)]) -> BTreeMap<Name, u32> {
+        islands.iter().map(|(island, count)| (Name::from(*island), *count)).collect()
+    }
+
+    fn status(region: &str, islands: &[(&str, u32)]) -> &'static str {
+        RegionIslands { region: region.into(), islands: counts(islands), reference: geo::region_island(region) }.status()
+    }
+
+    #[test]
+    fn dominant_island_breaks_ties_alphabetically() {
+        assert_eq!(dominant_island(&counts(&[("Visayas", 3), ("Luzon", 5)])), "Luzon");
+        assert_eq!(dominant_island(&counts(&[("Visayas", 2), ("Mindanao", 2)])), "Mindanao");
+        assert_eq!(dominant_island(&BTreeMap::<Name, u32>::new()), "");
+    }
+
+    #[test]
+    fn region_status_compares_with_the_reference() {
+        assert_eq!(status("Region I", &[("Luzon", 4)]), "Consistent");
+        assert_eq!(status("Region I", &[("Visayas", 4)]), "DiffersFromReference");
+        assert_eq!(status("Region I", &[("Luzon", 4), ("Visayas", 1)]), "Mixed");
+        assert_eq!(status("Region XX", &[("Luzon", 1)]), "NotInReference");
+    }
+
+    #[test]
+    fn island_averages_cover_completed_projects_only() {
+        let mut late = FloodControlProject::sample("P1", "Region I", "Builder A");
+        late.delay_days = Some(40);
+        let mut ongoing = FloodControlProject::sample("P2", "Region II", "Builder A");
+        ongoing.actual_completion_date = None;
+        ongoing.duration_days = None;
+        ongoing.delay_days = None;
+        ongoing.status = crate::status::ProjectStatus::Ongoing;
+        let mut visayas = FloodControlProject::sample("P3", "Region VI", "Builder B");
+        visayas.main_island = "Visayas".into();
+
+        let islands = aggregate_islands(&[late, ongoing, visayas]);
+        let luzon = &islands["Luzon"];
+        assert_eq!((luzon.projects, luzon.completed, luzon.regions.len()), (2, 1, 2));
+        assert_eq!((luzon.average(luzon.total_delay_days), luzon.high_delay_pct()), (40.0, 100.0));
+        assert_eq!(islands["Visayas"].high_delay_pct(), 0.0);
+        assert_eq!(IslandTotals::default().average(10), 0.0);
+    }
+}
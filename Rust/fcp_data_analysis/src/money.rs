@@ -0,0 +1,142 @@
+// Money parsing for the budget and contract cost columns. Strict mode only accepts plain
+// numbers; lenient mode also normalizes thousands separators, the peso sign or PHP code,
+// stray spaces, parenthesized negatives and K/M/B magnitude suffixes.
+
+use crate::config::MoneyMode;
+
+const CURRENCY_MARKERS: &[&str] = &["₱", "php"];
+
+pub enum MoneyParse {
+    Plain(f64),
+    Normalized(f64),    //accepted after normalization (lenient mode)
+    NeedsNormalization, //strict mode: only a lenient parse would accept it
+    Invalid,
+}
+
+impl MoneyParse {
+    pub fn amount(&self) -> Option<f64> {
+        match self {
+            MoneyParse::Plain(amount) | MoneyParse::Normalized(amount) => Some(*amount),
+            MoneyParse::NeedsNormalization | MoneyParse::Invalid => None,
+        }
+    }
+}
+
+//values that needed normalization, summed over the parsed rows
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MoneyCounts {
+    pub normalized: usize,
+    pub rejected_strict: usize,
+}
+
+impl MoneyCounts {
+    pub fn record(&mut self, parsed: &MoneyParse) {
+        match parsed {
+            MoneyParse::Normalized(_) => self.normalized += 1,
+            MoneyParse::NeedsNormalization => self.rejected_strict += 1,
+            MoneyParse::Plain(_) | MoneyParse::Invalid => {},
+        }
+    }
+
+    pub fn merge(&mut self, other: MoneyCounts) {
+        self.normalized += other.normalized;
+        self.rejected_strict += other.rejected_strict;
+    }
+}
+
+fn plain(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|amount| amount.is_finite())
+}
+
+//"₱ 1,234,567.89", "PHP 2.5M", "(5,000)", "1.2 b"
+fn normalize(value: &str) -> Option<f64> {
+    let mut text = value.trim().to_lowercase();
+
+    let mut negative = false;
+    if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        text = inner.to_string();
+        negative = true;
+    }
+    if let Some(rest) = text.trim_start().strip_prefix('-') {
+        text = rest.to_string();
+        negative = !negative;
+    }
+
+    for marker in CURRENCY_MARKERS {
+        text = text.replace(marker, "");
+    }
+    text.retain(|c| c != ',' && !c.is_whitespace());
+
+    let (digits, multiplier) = match text.chars().last() {
+        Some('k') => (&text[..text.len() - 1], 1e3),
+        Some('m') => (&text[..text.len() - 1], 1e6),
+        Some('b') => (&text[..text.len() - 1], 1e9),
+        _ => (text.as_str(), 1.0),
+    };
+
+    //only digits and one decimal point may remain
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let amount = digits.parse::<f64>().ok()? * multiplier;
+    Some(if negative { -amount } else { amount })
+}
+
+pub fn parse_money(value: &str, mode: MoneyMode) -> MoneyParse {
+    if let Some(amount) = plain(value) {
+        return MoneyParse::Plain(amount);
+    }
+    match (normalize(value), mode) {
+        (Some(amount), MoneyMode::Lenient) => MoneyParse::Normalized(amount),
+        (Some(_), MoneyMode::Strict) => MoneyParse::NeedsNormalization,
+        (None, _) => MoneyParse::Invalid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lenient(value: &str) -> Option<f64> {
+        parse_money(value, MoneyMode::Lenient).amount()
+    }
+
+    #[test]
+    fn plain_numbers_parse_in_either_mode() {
+        assert!(matches!(parse_money(" 1234.5 ", MoneyMode::Strict), MoneyParse::Plain(amount) if amount == 1234.5));
+        assert!(matches!(parse_money("-20", MoneyMode::Lenient), MoneyParse::Plain(amount) if amount == -20.0));
+        assert!(matches!(parse_money("inf", MoneyMode::Lenient), MoneyParse::Invalid));
+    }
+
+    #[test]
+    fn lenient_mode_normalizes_formatted_amounts() {
+        assert_eq!(lenient("₱ 1,234,567.89"), Some(1_234_567.89));
+        assert_eq!(lenient("PHP 2.5M"), Some(2_500_000.0));
+        assert_eq!(lenient("1.2 b"), Some(1_200_000_000.0));
+        assert_eq!(lenient("750k"), Some(750_000.0));
+        assert_eq!(lenient("(5,000)"), Some(-5_000.0));
+        assert_eq!(lenient("-(5,000)"), None);
+    }
+
+    #[test]
+    fn rejects_text_and_malformed_numbers() {
+        for value in ["", "N/A", "TBD", "1.2.3", "12x", "k", "₱"] {
+            assert!(matches!(parse_money(value, MoneyMode::Lenient), MoneyParse::Invalid), "{}", value);
+        }
+    }
+
+    #[test]
+    fn strict_mode_counts_what_lenient_would_accept() {
+        let mut counts = MoneyCounts::default();
+        for value in ["1,000", "100", "N/A"] {
+            counts.record(&parse_money(value, MoneyMode::Strict));
+        }
+        assert_eq!((counts.normalized, counts.rejected_strict), (0, 1));
+        assert_eq!(parse_money("1,000", MoneyMode::Strict).amount(), None);
+
+        let mut lenient_counts = MoneyCounts::default();
+        lenient_counts.record(&parse_money("1,000", MoneyMode::Lenient));
+        counts.merge(lenient_counts);
+        assert_eq!((counts.normalized, counts.rejected_strict), (1, 1));
+    }
+}
@@ -0,0 +1,65 @@
+// Guards for derived metrics whose inputs can make them meaningless: a zero or non-finite
+// denominator, no delay to divide savings by, or scores with no spread to normalize over.
+// Such a metric is None rather than 0.0 or NaN, and is written as "undefined" in the
+// console tables, the CSV reports and the JSON endpoints.
+
+use serde::Serializer;
+
+pub const UNDEFINED: &str = "undefined";
+
+pub fn finite(value: f64) -> Option<f64> {
+    Some(value).filter(|v| v.is_finite())
+}
+
+//None for a zero or non-finite denominator, or a result that overflows
+pub fn ratio(numerator: f64, denominator: f64) -> Option<f64> {
+    if denominator == 0.0 || !denominator.is_finite() {
+        return None;
+    }
+    finite(numerator / denominator)
+}
+
+//two decimals, as the reports print every metric
+pub fn format_defined(value: Option<f64>) -> String {
+    value.map_or_else(|| UNDEFINED.to_string(), |v| format!("{:.2}", v))
+}
+
+//a number when defined, the string "undefined" otherwise
+pub fn serialize_defined<S: Serializer>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => serializer.serialize_f64(*v),
+        None => serializer.serialize_str(UNDEFINED),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Score {
+        #[serde(serialize_with = "serialize_defined")]
+        value: Option<f64>,
+    }
+
+    #[test]
+    fn ratio_needs_a_usable_denominator() {
+        assert_eq!(ratio(1.0, 4.0), Some(0.25));
+        assert_eq!(ratio(-3.0, 2.0), Some(-1.5));
+        assert_eq!(ratio(1.0, 0.0), None);
+        assert_eq!(ratio(1.0, -0.0), None);
+        assert_eq!(ratio(1.0, f64::NAN), None);
+        assert_eq!(ratio(1.0, f64::INFINITY), None);
+        assert_eq!(ratio(f64::NAN, 2.0), None);
+        assert_eq!(ratio(f64::MAX, 0.5), None);
+    }
+
+    #[test]
+    fn undefined_values_are_written_as_text() {
+        assert_eq!(finite(f64::NEG_INFINITY), None);
+        assert_eq!(format_defined(Some(2.0 / 3.0)), "0.67");
+        assert_eq!(format_defined(None), "undefined");
+        assert_eq!(serde_json::to_string(&Score { value: Some(1.5) }).unwrap(), r#"{"value":1.5}"#);
+        assert_eq!(serde_json::to_string(&Score { value: None }).unwrap(), r#"{"value":"undefined"}"#);
+    }
+}
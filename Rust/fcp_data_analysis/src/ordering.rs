@@ -0,0 +1,231 @@
+// Row order of the three reports. Rows are sorted by the keys in the sorting section of
+// data/fcp_config.json, then by the report's name columns, so every ordering is total and
+// ties come out the same on every run. Numbers compare with total_cmp; NaN and missing
+// values sort last in either direction.
+
+use std::cmp::Ordering;
+use std::error::Error;
+
+use crate::config::{Direction, SortConfig, SortKey, CONFIG_FILENAME};
+use crate::{ContractorStats, CostOverrunStats, RegionalStats};
+
+pub enum SortValue<'a> {
+    Number(f64),
+    Missing, //a number with no value, e.g. YoYChange without an earlier year or an undefined score
+    Text(&'a str),
+}
+
+pub trait Sortable {
+    //sortable columns, named as in the report's CSV header
+    const COLUMNS: &'static [&'static str];
+    //name columns appended to every ordering, ascending
+    const TIE_BREAK: &'static [&'static str];
+
+    fn sort_value(&self, column: &str) -> SortValue<'_>;
+}
+
+fn optional(value: Option<f64>) -> SortValue<'static> {
+    value.map_or(SortValue::Missing, SortValue::Number)
+}
+
+impl Sortable for RegionalStats {
+    const COLUMNS: &'static [&'static str] =
+        &["Region", "MainIsland", "TotalBudget", "MedianSavings", "AvgDuration", "AvgDelay", "HighDelay%", "EfficiencyScore"];
+    const TIE_BREAK: &'static [&'static str] = &["Region"];
+
+    fn sort_value(&self, column: &str) -> SortValue<'_> {
+        match column {
+            "Region" => SortValue::Text(&self.region),
+            "MainIsland" => SortValue::Text(&self.main_island),
+            "TotalBudget" => SortValue::Number(self.total_budget),
+            "MedianSavings" => SortValue::Number(self.median_savings),
+            "AvgDuration" => SortValue::Number(self.avg_duration),
+            "AvgDelay" => SortValue::Number(self.avg_delay),
+            "HighDelay%" => SortValue::Number(self.high_delay_pct),
+            _ => optional(self.efficiency_score),
+        }
+    }
+}
+
+impl Sortable for ContractorStats {
+    const COLUMNS: &'static [&'static str] = &["Contractor", "ProjectCount", "TotalContractCost", "AvgDurationDays",
+        "AvgCompletionDelayDays", "TotalCostSavings", "ReliabilityIndex", "RiskFlag"];
+    const TIE_BREAK: &'static [&'static str] = &["Contractor"];
+
+    fn sort_value(&self, column: &str) -> SortValue<'_> {
+        match column {
+            "Contractor" => SortValue::Text(&self.contractor),
+            "ProjectCount" => SortValue::Number(self.project_count as f64),
+            "AvgDurationDays" => SortValue::Number(self.avg_duration_days),
+            "AvgCompletionDelayDays" => SortValue::Number(self.avg_completion_delay_days),
+            "TotalCostSavings" => SortValue::Number(self.total_cost_savings),
+            "ReliabilityIndex" => optional(self.reliability_index),
+            "RiskFlag" => SortValue::Text(&self.risk_flag),
+            _ => SortValue::Number(self.total_contract_cost),
+        }
+    }
+}
+
+impl Sortable for CostOverrunStats {
+    const COLUMNS: &'static [&'static str] = &["FundingYear", "TypeOfWork", "TotalProjects", "AvgCostSavings", "OverrunRate",
+        "AvgDurationDays", "AvgDelayDays", "YoYChange", "YoYBaseYear", "CAGR"];
+    const TIE_BREAK: &'static [&'static str] = &["FundingYear", "TypeOfWork"];
+
+    fn sort_value(&self, column: &str) -> SortValue<'_> {
+        match column {
+            "TypeOfWork" => SortValue::Text(&self.type_of_work),
+            "TotalProjects" => SortValue::Number(self.total_projects as f64),
+            "AvgCostSavings" => SortValue::Number(self.avg_cost_savings),
+            "OverrunRate" => SortValue::Number(self.overrun_rate),
+            "AvgDurationDays" => SortValue::Number(self.avg_duration_days),
+            "AvgDelayDays" => SortValue::Number(self.avg_delay_days),
+            "YoYChange" => optional(self.yoy_change),
+            "YoYBaseYear" => optional(self.yoy_base_year.map(f64::from)),
+            "CAGR" => optional(self.cagr),
+            _ => SortValue::Number(self.funding_year as f64),
+        }
+    }
+}
+
+//NaN and missing values last whatever the direction
+fn compare_values(a: &SortValue, b: &SortValue, direction: Direction) -> Ordering {
+    let directed = |ordering: Ordering| if direction == Direction::Desc { ordering.reverse() } else { ordering };
+    match (a, b) {
+        (SortValue::Text(a), SortValue::Text(b)) => directed(a.cmp(b)),
+        (SortValue::Number(a), SortValue::Number(b)) if !a.is_nan() && !b.is_nan() => directed(a.total_cmp(b)),
+        (SortValue::Number(a), _) if !a.is_nan() => Ordering::Less,
+        (_, SortValue::Number(b)) if !b.is_nan() => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+pub fn sort_rows<T: Sortable>(rows: &mut [T], keys: &[SortKey]) {
+    let tie_break = T::TIE_BREAK.iter().map(|column| (*column, Direction::Asc));
+    let order: Vec<(&str, Direction)> = keys.iter().map(|key| (key.column.as_str(), key.direction)).chain(tie_break).collect();
+
+    rows.sort_by(|a, b| {
+        order
+            .iter()
+            .map(|(column, direction)| compare_values(&a.sort_value(column), &b.sort_value(column), *direction))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+//"TotalContractCost desc, Contractor asc"
+pub fn describe<T: Sortable>(keys: &[SortKey]) -> String {
+    let tie_break = T::TIE_BREAK.iter().filter(|column| !keys.iter().any(|key| key.column == **column)).map(|column| format!("{} asc", column));
+    keys.iter()
+        .map(|key| format!("{} {}", key.column, if key.direction == Direction::Desc { "desc" } else { "asc" }))
+        .chain(tie_break)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+//"TotalBudget:desc,Region" (ascending unless :desc is given)
+pub fn parse_keys<T: Sortable>(text: &str) -> Result<Vec<SortKey>, String> {
+    text.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let (column, direction) = part.trim().split_once(':').unwrap_or((part.trim(), "asc"));
+            let direction = match direction.to_ascii_lowercase().as_str() {
+                "asc" => Direction::Asc,
+                "desc" => Direction::Desc,
+                other => return Err(format!("Invalid sort direction {} (use asc or desc)", other)),
+            };
+            if !T::COLUMNS.contains(&column) {
+                return Err(format!("Unknown sort column {} (columns: {})", column, T::COLUMNS.join(", ")));
+            }
+            Ok(SortKey { column: column.to_string(), direction })
+        })
+        .collect()
+}
+
+fn check_keys<T: Sortable>(section: &str, keys: &[SortKey]) -> Result<(), Box<dyn Error>> {
+    match keys.iter().find(|key| !T::COLUMNS.contains(&key.column.as_str())) {
+        Some(key) => Err(format!("Unknown column {} in sorting.{} of data/{} (columns: {})",
+            key.column, section, CONFIG_FILENAME, T::COLUMNS.join(", ")).into()),
+        None => Ok(()),
+    }
+}
+
+//rejects sort keys naming columns the report does not have
+pub fn check_sorting(config: &SortConfig) -> Result<(), Box<dyn Error>> {
+    check_keys::<RegionalStats>("regional", &config.regional)?;
+    check_keys::<ContractorStats>("contractors", &config.contractors)?;
+    check_keys::<CostOverrunStats>("trends", &config.trends)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(name: &str, total_budget: f64, efficiency_score: Option<f64>) -> RegionalStats {
+        RegionalStats {
+            region: name.to_string(),
+            main_island: "Luzon".to_string(),
+            total_budget,
+            median_savings: 0.0,
+            avg_duration: 0.0,
+            avg_delay: 0.0,
+            high_delay_pct: 0.0,
+            efficiency_score,
+        }
+    }
+
+    fn sorted(mut rows: Vec<RegionalStats>, column: &str, direction: Direction) -> Vec<String> {
+        sort_rows(&mut rows, &[SortKey { column: column.to_string(), direction }]);
+        rows.into_iter().map(|row| row.region).collect()
+    }
+
+    #[test]
+    fn nan_and_missing_sort_last_in_either_direction() {
+        for direction in [Direction::Asc, Direction::Desc] {
+            let (number, nan, missing) = (SortValue::Number(1.0), SortValue::Number(f64::NAN), SortValue::Missing);
+            assert_eq!(compare_values(&number, &nan, direction), Ordering::Less);
+            assert_eq!(compare_values(&missing, &number, direction), Ordering::Greater);
+            assert_eq!(compare_values(&nan, &missing, direction), Ordering::Equal);
+            assert_eq!(compare_values(&nan, &nan, direction), Ordering::Equal);
+        }
+        assert_eq!(compare_values(&SortValue::Number(1.0), &SortValue::Number(2.0), Direction::Desc), Ordering::Greater);
+        assert_eq!(compare_values(&SortValue::Number(-0.0), &SortValue::Number(0.0), Direction::Asc), Ordering::Less);
+    }
+
+    #[test]
+    fn sorts_undefined_scores_after_every_number() {
+        let rows = || vec![
+            region("A", 1.0, None),
+            region("B", 1.0, Some(f64::NAN)),
+            region("C", 1.0, Some(5.0)),
+            region("D", 1.0, Some(-2.0)),
+        ];
+        assert_eq!(sorted(rows(), "EfficiencyScore", Direction::Desc), vec!["C", "D", "A", "B"]);
+        assert_eq!(sorted(rows(), "EfficiencyScore", Direction::Asc), vec!["D", "C", "A", "B"]);
+    }
+
+    #[test]
+    fn ties_fall_back_to_the_name_columns() {
+        let rows = vec![region("Region II", 5.0, None), region("Region I", 5.0, None), region("Region III", 9.0, None)];
+        assert_eq!(sorted(rows, "TotalBudget", Direction::Desc), vec!["Region III", "Region I", "Region II"]);
+    }
+
+    #[test]
+    fn parses_sort_keys() {
+        let keys = parse_keys::<RegionalStats>("TotalBudget:DESC, Region").unwrap();
+        let parsed: Vec<(&str, Direction)> = keys.iter().map(|key| (key.column.as_str(), key.direction)).collect();
+        assert_eq!(parsed, vec![("TotalBudget", Direction::Desc), ("Region", Direction::Asc)]);
+        assert_eq!(describe::<RegionalStats>(&keys), "TotalBudget desc, Region asc");
+        assert_eq!(describe::<CostOverrunStats>(&keys[..1]), "TotalBudget desc, FundingYear asc, TypeOfWork asc");
+
+        assert!(parse_keys::<RegionalStats>("").unwrap().is_empty());
+        assert!(parse_keys::<RegionalStats>("TotalBudget:up").is_err());
+        assert!(parse_keys::<RegionalStats>("Contractor").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_configured_columns() {
+        assert!(check_sorting(&SortConfig::default()).is_ok());
+        let config = SortConfig { trends: vec![SortKey { column: "Region".to_string(), direction: Direction::Asc }], ..SortConfig::default() };
+        assert!(check_sorting(&config).is_err());
+    }
+}
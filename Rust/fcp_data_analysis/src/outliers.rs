@@ -0,0 +1,222 @@
+// Outlier report: flags projects whose savings ratio, delay, duration or contract cost
+// stand out within their region/type-of-work peer group, plus projects whose
+// completion date precedes the start date or whose timeline is implausibly long.
+// Delay and duration peers are completed projects only, as in the other reports.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::dates;
+use crate::statistics;
+use crate::status::ProjectStatus;
+use crate::{data_path, format_currency, metric_delay_days, metric_duration_days, savings_ratio, FloodControlProject};
+
+const MIN_PEER_GROUP_SIZE: usize = 5;
+const ROBUST_Z_THRESHOLD: f64 = 3.5;
+const IQR_FENCE_MULTIPLIER: f64 = 1.5;
+//scales the MAD so the robust z-score is comparable to a normal z-score
+const MAD_SCALE: f64 = 0.6745;
+
+struct FlaggedProject<'a> {
+    project: &'a FloodControlProject,
+    reasons: Vec<String>,
+}
+
+struct PeerMetric {
+    name: &'static str,
+    value: fn(&FloodControlProject) -> Option<f64>, //None when the metric does not apply to the project
+}
+
+const PEER_METRICS: [PeerMetric; 4] = [
+    PeerMetric { name: "SavingsRatio%", value: |p| Some(savings_ratio(p)) },
+    PeerMetric { name: "DelayDays", value: |p| metric_delay_days(p).map(|d| d as f64) },
+    PeerMetric { name: "DurationDays", value: |p| metric_duration_days(p).map(|d| d as f64) },
+    PeerMetric { name: "ContractCost", value: |p| Some(p.contract_cost) },
+];
+
+fn check_peer_group(members: &[&FloodControlProject], reasons: &mut [Vec<String>]) {
+    for metric in &PEER_METRICS {
+        let values: Vec<Option<f64>> = members.iter().map(|p| (metric.value)(p)).collect();
+        let present: Vec<f64> = values.iter().flatten().copied().collect();
+        if present.len() < MIN_PEER_GROUP_SIZE {
+            continue; //too few peers for a meaningful comparison
+        }
+
+        let median = statistics::median(&present);
+        let mad = statistics::mad(&present);
+        let (lower_fence, upper_fence) = statistics::iqr_fences(&present, IQR_FENCE_MULTIPLIER);
+
+        for (index, value) in values.iter().enumerate() {
+            let Some(value) = value else { continue };
+            if mad > 0.0 {
+                let robust_z = MAD_SCALE * (value - median) / mad;
+                if robust_z.abs() > ROBUST_Z_THRESHOLD {
+                    reasons[index].push(format!("{} robust z-score {:.2} (peer median {:.2})", metric.name, robust_z, median));
+                }
+            }
+
+            if *value < lower_fence || *value > upper_fence {
+                reasons[index].push(format!("{} {:.2} outside IQR fences [{:.2}, {:.2}]", metric.name, value, lower_fence, upper_fence));
+            }
+        }
+    }
+}
+
+fn detect_outliers(projects: &[FloodControlProject]) -> Vec<FlaggedProject<'_>> {
+    let mut peer_groups: BTreeMap<(&str, &str), Vec<usize>> = BTreeMap::new();
+    // (region, type_of_work): indices into projects
+    for (index, project) in projects.iter().enumerate() {
+        peer_groups.entry((&project.region, &project.type_of_work)).or_default().push(index);
+    }
+
+    let mut reasons: Vec<Vec<String>> = vec![Vec::new(); projects.len()];
+
+    for indices in peer_groups.values() {
+        let members: Vec<&FloodControlProject> = indices.iter().map(|&i| &projects[i]).collect();
+        let mut group_reasons: Vec<Vec<String>> = vec![Vec::new(); members.len()];
+        check_peer_group(&members, &mut group_reasons);
+
+        for (&index, found) in indices.iter().zip(group_reasons) {
+            reasons[index].extend(found);
+        }
+    }
+
+    for (index, project) in projects.iter().enumerate() {
+        let duration_days = project.duration_days.unwrap_or_default();
+        match project.status {
+            ProjectStatus::CompletionBeforeStart => {
+                reasons[index].push(format!("Completion date precedes start date ({} days)", duration_days));
+            },
+            ProjectStatus::ImplausiblyLong => {
+                reasons[index].push(format!("Implausibly long timeline ({} days)", duration_days));
+            },
+            ProjectStatus::Completed | ProjectStatus::Ongoing => {},
+        }
+    }
+
+    projects
+        .iter()
+        .zip(reasons)
+        .filter(|(_, found)| !found.is_empty())
+        .map(|(project, found)| FlaggedProject { project, reasons: found })
+        .collect()
+}
+
+fn display_outlier_report(flagged: &[FlaggedProject]) {
+    println!("\nOutlier and Anomaly Detection Report");
+    println!("(Peer group: Region + TypeOfWork with at least {} projects; robust |z| > {}, {}x IQR fences)",
+        MIN_PEER_GROUP_SIZE, ROBUST_Z_THRESHOLD, IQR_FENCE_MULTIPLIER);
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<15} | {:<35} | {:>17} | {:>10} | {:>8} | {:<40} |",
+             "ProjectId", "Region", "ContractCost", "Savings%", "Delay", "Reasons");
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for flag in flagged {
+        let region: String = flag.project.region.chars().take(35).collect();
+        let first_reason: String = flag.reasons[0].chars().take(34).collect();
+
+        println!("| {:<15} | {:<35} | {:>17} | {:>10.2} | {:>8} | {:<40} |",
+            flag.project.project_id,
+            region,
+            format_currency(flag.project.contract_cost),
+            savings_ratio(flag.project),
+            dates::format_days(flag.project.delay_days),
+            format!("{} (+{})", first_reason, flag.reasons.len() - 1)
+        );
+    }
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("{} projects flagged. Full reasons are listed in the exported CSV.", flagged.len());
+}
+
+fn export_outliers_csv(flagged: &[FlaggedProject], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["ProjectId", "ContractId", "Region", "Province", "TypeOfWork", "FundingYear", "Contractor",
+        "ContractCost", "SavingsRatio%", "DurationDays", "CompletionDelayDays", "ReasonCount", "Reasons"])?;
+
+    //format and write data
+    for flag in flagged {
+        let project = flag.project;
+        writer.write_record([
+            project.project_id.as_str(),
+            &project.contract_id,
+            &project.region,
+            &project.province,
+            &project.type_of_work,
+            &project.funding_year.to_string(),
+            &project.contractor,
+            &format_currency(project.contract_cost),
+            &format!("{:.2}", savings_ratio(project)),
+            &dates::format_days(project.duration_days),
+            &dates::format_days(project.delay_days),
+            &flag.reasons.len().to_string(),
+            &flag.reasons.join("; "),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn outlier_report(projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    let flagged = detect_outliers(projects);
+
+    display_outlier_report(&flagged);
+    export_outliers_csv(&flagged, "report_outliers.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peers() -> Vec<FloodControlProject> {
+        (0..8)
+            .map(|i| {
+                let mut project = FloodControlProject::sample(&format!("P{}", i), "Region I", "Builder A");
+                project.delay_days = Some(i as i64);
+                project.duration_days = Some(100 + i as i64);
+                project
+            })
+            .collect()
+    }
+
+    fn reasons_for(projects: &[FloodControlProject]) -> Vec<Vec<String>> {
+        let members: Vec<&FloodControlProject> = projects.iter().collect();
+        let mut reasons = vec![Vec::new(); members.len()];
+        check_peer_group(&members, &mut reasons);
+        reasons
+    }
+
+    #[test]
+    fn flags_a_completed_project_with_an_extreme_delay() {
+        let mut projects = peers();
+        projects[0].delay_days = Some(900);
+        projects[0].duration_days = Some(1000);
+        let reasons = reasons_for(&projects);
+        assert!(reasons[0].iter().any(|r| r.starts_with("DelayDays")));
+        assert!(reasons[0].iter().any(|r| r.starts_with("DurationDays")));
+    }
+
+    #[test]
+    fn leaves_projects_outside_the_delay_metrics_out_of_the_peer_group() {
+        let mut projects = peers();
+        projects[0].delay_days = Some(-900);
+        projects[0].duration_days = Some(-800);
+        projects[0].status = ProjectStatus::CompletionBeforeStart;
+        let reasons = reasons_for(&projects);
+        assert!(reasons[0].iter().all(|r| !r.starts_with("DelayDays") && !r.starts_with("DurationDays")));
+    }
+
+    #[test]
+    fn skips_groups_below_the_minimum_size() {
+        let mut projects = peers();
+        projects.truncate(MIN_PEER_GROUP_SIZE - 1);
+        projects[0].contract_cost = 1.0e12;
+        assert!(reasons_for(&projects).iter().all(|r| r.is_empty()));
+    }
+}
@@ -0,0 +1,316 @@
+// Source layout detection: encoding (UTF-8, UTF-8 with BOM, Windows-1252), delimiter, and
+// the mapping of the file's headers onto the column names RawFloodControlProject expects.
+// Headers match case- and punctuation-insensitively, then through the aliases in the
+// columns section of data/fcp_config.json, then through the built-in aliases below.
+
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::io::{BufRead, Read};
+
+use csv::StringRecord;
+
+use crate::config::{ColumnConfig, CONFIG_FILENAME};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+//(column, required, built-in aliases seen in other agencies' and older DPWH exports)
+const COLUMNS: &[(&str, bool, &[&str])] = &[
+    ("MainIsland", true, &["Island", "IslandGroup"]),
+    ("Region", true, &["RegionName"]),
+    ("Province", true, &["ProvinceName"]),
+    ("ProjectId", true, &["ProjectCode", "ProjId"]),
+    ("ProjectName", true, &["ProjectDescription", "ProjectTitle", "NameOfProject"]),
+    ("TypeOfWork", true, &["WorkType", "ProjectType", "Category"]),
+    ("FundingYear", true, &["FundYear", "InfraYear", "Year"]),
+    ("ContractId", true, &["ContractNo", "ContractNumber", "ContractCode"]),
+    ("ApprovedBudgetForContract", true, &["ABC", "ApprovedBudgetForTheContract", "ApprovedBudget", "Budget"]),
+    ("ContractCost", true, &["ContractAmount", "AwardedAmount", "ContractPrice", "Cost"]),
+    ("ActualCompletionDate", true, &["CompletionDate", "DateCompleted", "ActualDateOfCompletion", "DateOfCompletion"]),
+    ("Contractor", true, &["ContractorName", "WinningContractor", "AwardedTo"]),
+    ("StartDate", true, &["DateStarted", "NoticeToProceedDate", "NTPDate"]),
+    ("ProjectLatitude", false, &["Latitude", "Lat"]),
+    ("ProjectLongitude", false, &["Longitude", "Lon", "Lng"]),
+    ("ProvincialCapital", false, &["Capital"]),
+    ("ProvincialCapitalLatitude", false, &["CapitalLatitude"]),
+    ("ProvincialCapitalLongitude", false, &["CapitalLongitude"]),
+    ("ContractDuration", false, &["Duration", "ContractDurationDays", "CalendarDays"]),
+    ("TargetCompletionDate", false, &["TargetDate", "OriginalCompletionDate", "ExpectedCompletionDate"]),
+];
+
+//Windows-1252 bytes 0x80-0x9F; the rest of the upper half matches Latin-1
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+//(source header, column) for each header that was renamed
+pub type ColumnMapping = Vec<(String, &'static str)>;
+
+pub type SourceReader<'a> = Box<dyn Read + 'a>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    Windows1252,
+}
+
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub encoding: Encoding,
+    pub delimiter: u8,
+    pub mapped: ColumnMapping,
+}
+
+impl Layout {
+    //None for the standard layout: UTF-8, comma-delimited, exact headers
+    pub fn describe(&self) -> Option<String> {
+        let mut notes: Vec<String> = Vec::new();
+        match self.encoding {
+            Encoding::Utf8 => {},
+            Encoding::Utf8Bom => notes.push("UTF-8 with BOM".to_string()),
+            Encoding::Windows1252 => notes.push("Windows-1252".to_string()),
+        }
+        if self.delimiter != b',' {
+            notes.push(format!("{:?}-delimited", self.delimiter as char));
+        }
+        if !self.mapped.is_empty() {
+            let pairs: Vec<String> = self.mapped.iter().map(|(source, column)| format!("{} → {}", source, column)).collect();
+            notes.push(format!("columns mapped: {}", pairs.join(", ")));
+        }
+        if notes.is_empty() { None } else { Some(notes.join("; ")) }
+    }
+}
+
+pub fn is_known_column(name: &str) -> bool {
+    COLUMNS.iter().any(|(column, _, _)| *column == name)
+}
+
+//lowercase letters and digits only, so "Approved Budget for Contract" matches "ApprovedBudgetForContract"
+pub fn header_key(header: &str) -> String {
+    header.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+fn detect_encoding(sample: &[u8]) -> Encoding {
+    if sample.starts_with(UTF8_BOM) {
+        return Encoding::Utf8Bom;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => Encoding::Utf8,
+        //a multi-byte character cut off at the end of the sample is still UTF-8
+        Err(e) if e.error_len().is_none() => Encoding::Utf8,
+        Err(_) => Encoding::Windows1252,
+    }
+}
+
+//the candidate that splits the header line (outside quotes) the most times
+fn detect_delimiter(sample: &[u8]) -> u8 {
+    let header_line = sample.split(|b| *b == b'\n').next().unwrap_or_default();
+    let mut counts = [0usize; DELIMITERS.len()];
+    let mut quoted = false;
+    for byte in header_line {
+        if *byte == b'"' {
+            quoted = !quoted;
+        } else if !quoted && let Some(index) = DELIMITERS.iter().position(|d| d == byte) {
+            counts[index] += 1;
+        }
+    }
+
+    let (best, count) = counts.iter().enumerate().max_by_key(|(index, count)| (**count, usize::MAX - index)).unwrap_or((0, &0));
+    if *count > 0 { DELIMITERS[best] } else { b',' }
+}
+
+//transcodes Windows-1252 to UTF-8 while reading
+struct Windows1252Reader<R> {
+    inner: R,
+    pending: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> Read for Windows1252Reader<R> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        if self.position == self.pending.len() {
+            let mut raw = [0u8; 8192];
+            let read = self.inner.read(&mut raw)?;
+            self.pending.clear();
+            self.position = 0;
+            for byte in &raw[..read] {
+                let c = match byte {
+                    0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+                    _ => *byte as char,
+                };
+                let mut buffer = [0u8; 4];
+                self.pending.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+
+        let available = &self.pending[self.position..];
+        let len = available.len().min(out.len());
+        out[..len].copy_from_slice(&available[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+//detects the encoding and delimiter from the start of `input` and returns a UTF-8 reader without the BOM
+pub fn open_source<'a, R: BufRead + 'a>(mut input: R) -> Result<(SourceReader<'a>, Encoding, u8), Box<dyn Error>> {
+    let sample = input.fill_buf()?;
+    let encoding = detect_encoding(sample);
+    let delimiter = detect_delimiter(sample.strip_prefix(UTF8_BOM).unwrap_or(sample));
+
+    let reader: SourceReader<'a> = match encoding {
+        Encoding::Utf8 => Box::new(input),
+        Encoding::Utf8Bom => {
+            input.consume(UTF8_BOM.len());
+            Box::new(input)
+        },
+        Encoding::Windows1252 => Box::new(Windows1252Reader { inner: input, pending: Vec::new(), position: 0 }),
+    };
+    Ok((reader, encoding, delimiter))
+}
+
+//renames the file's headers to the expected column names; errors when required columns are missing
+pub fn map_headers(headers: &StringRecord, config: &ColumnConfig) -> Result<(StringRecord, ColumnMapping), Box<dyn Error>> {
+    for column in config.aliases.keys() {
+        if !is_known_column(column) {
+            return Err(format!("Unknown column {} in columns.aliases of data/{}", column, CONFIG_FILENAME).into());
+        }
+    }
+
+    let keys: Vec<String> = headers.iter().map(header_key).collect();
+    let mut assigned: BTreeMap<usize, &'static str> = BTreeMap::new();
+    let mut missing: Vec<&str> = Vec::new();
+
+    for (column, required, built_in) in COLUMNS {
+        let configured = config.aliases.get(*column).map(Vec::as_slice).unwrap_or_default();
+        let candidates: Vec<String> = std::iter::once(*column)
+            .chain(configured.iter().map(String::as_str))
+            .chain(built_in.iter().copied())
+            .map(header_key)
+            .collect();
+
+        //earlier candidates win; a source column is used for one column only
+        let found = candidates.iter().find_map(|candidate| {
+            keys.iter().enumerate().position(|(index, key)| key == candidate && !assigned.contains_key(&index))
+        });
+        match found {
+            Some(index) => {
+                assigned.insert(index, column);
+            },
+            None if *required => missing.push(column),
+            None => {},
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing required columns: {} (found: {}). Map the file's header names to them under columns.aliases in data/{}",
+            missing.join(", "),
+            headers.iter().collect::<Vec<&str>>().join(", "),
+            CONFIG_FILENAME
+        ).into());
+    }
+
+    //unmatched columns keep their names unless that would clash with an expected one
+    let expected: HashSet<&str> = assigned.values().copied().collect();
+    let mut mapped: ColumnMapping = Vec::new();
+    let renamed: StringRecord = headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| match assigned.get(&index) {
+            Some(column) => {
+                if header != *column {
+                    mapped.push((header.to_string(), *column));
+                }
+                column.to_string()
+            },
+            None if expected.contains(header) => String::new(),
+            None => header.to_string(),
+        })
+        .collect();
+
+    Ok((renamed, mapped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STANDARD_HEADERS: [&str; 13] = [
+        "MainIsland", "Region", "Province", "ProjectId", "ProjectName", "TypeOfWork", "FundingYear", "ContractId",
+        "ApprovedBudgetForContract", "ContractCost", "ActualCompletionDate", "Contractor", "StartDate",
+    ];
+
+    fn read_all(input: &[u8]) -> (String, Encoding, u8) {
+        let (mut reader, encoding, delimiter) = open_source(input).unwrap();
+        let mut text = String::new();
+        reader.read_to_string(&mut text).unwrap();
+        (text, encoding, delimiter)
+    }
+
+    #[test]
+    fn detects_encodings() {
+        assert_eq!(read_all(b"Region,Province\n"), ("Region,Province\n".to_string(), Encoding::Utf8, b','));
+        assert_eq!(read_all(b"\xEF\xBB\xBFRegion\n").0, "Region\n");
+        assert_eq!(read_all(b"\xEF\xBB\xBFRegion\n").1, Encoding::Utf8Bom);
+        assert_eq!(read_all(b"Pe\xF1a \x80 \x93x\x94\n"), ("Peña € “x”\n".to_string(), Encoding::Windows1252, b','));
+        //a UTF-8 character cut off by the end of the sample
+        assert_eq!(detect_encoding("ñ".as_bytes().split_last().unwrap().1), Encoding::Utf8);
+    }
+
+    #[test]
+    fn detects_delimiters_outside_quotes() {
+        assert_eq!(detect_delimiter(b"a;b;c\n1,5;2;3"), b';');
+        assert_eq!(detect_delimiter(b"a\tb\tc"), b'\t');
+        assert_eq!(detect_delimiter(b"\"a,b,c\"|d|e"), b'|');
+        assert_eq!(detect_delimiter(b"single"), b',');
+        //ties go to the earlier candidate
+        assert_eq!(detect_delimiter(b"a;b,c"), b',');
+    }
+
+    #[test]
+    fn maps_aliases_and_spelled_out_headers() {
+        let mut headers: Vec<&str> = STANDARD_HEADERS.to_vec();
+        headers[8] = "Approved Budget for the Contract";
+        headers[11] = "Supplier";
+        let config = ColumnConfig { aliases: BTreeMap::from([("Contractor".to_string(), vec!["Supplier".to_string()])]) };
+
+        let (renamed, mapped) = map_headers(&StringRecord::from(headers), &config).unwrap();
+        assert_eq!(renamed.iter().collect::<Vec<_>>(), STANDARD_HEADERS.to_vec());
+        assert_eq!(mapped, vec![
+            ("Approved Budget for the Contract".to_string(), "ApprovedBudgetForContract"),
+            ("Supplier".to_string(), "Contractor"),
+        ]);
+    }
+
+    #[test]
+    fn reports_missing_and_unknown_columns() {
+        let error = map_headers(&StringRecord::from(STANDARD_HEADERS[..12].to_vec()), &ColumnConfig::default()).unwrap_err();
+        assert!(error.to_string().starts_with("Missing required columns: StartDate"));
+
+        let config = ColumnConfig { aliases: BTreeMap::from([("Budgett".to_string(), vec![])]) };
+        assert!(map_headers(&StringRecord::from(STANDARD_HEADERS.to_vec()), &config).is_err());
+    }
+
+    #[test]
+    fn blanks_unmatched_headers_that_clash() {
+        //the first matching header is used, so a later exact "ContractCost" would duplicate the column
+        let mut headers: Vec<&str> = STANDARD_HEADERS.to_vec();
+        headers[9] = "Contract Cost";
+        headers.push("ContractCost");
+        headers.push("Remarks");
+        let (renamed, mapped) = map_headers(&StringRecord::from(headers), &ColumnConfig::default()).unwrap();
+        assert_eq!(renamed.get(9), Some("ContractCost"));
+        assert_eq!(renamed.get(13), Some(""));
+        assert_eq!(renamed.get(14), Some("Remarks"));
+        assert_eq!(mapped, vec![("Contract Cost".to_string(), "ContractCost")]);
+    }
+
+    #[test]
+    fn describes_only_non_standard_layouts() {
+        assert_eq!(Layout { encoding: Encoding::Utf8, delimiter: b',', mapped: Vec::new() }.describe(), None);
+        let layout = Layout { encoding: Encoding::Windows1252, delimiter: b';', mapped: vec![("ABC".to_string(), "ApprovedBudgetForContract")] };
+        assert_eq!(layout.describe().unwrap(), "Windows-1252; ';'-delimited; columns mapped: ABC → ApprovedBudgetForContract");
+    }
+}
@@ -0,0 +1,351 @@
+// Local HTTP/JSON API for the dashboard.
+// The CSV is loaded once by the caller; every request is answered from the in-memory projects.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::config::{SortConfig, SortKey};
+use crate::ordering::{self, Sortable};
+use crate::{
+    build_contractor_report, build_cost_report, build_regional_report, compute_summary,
+    ContractorStats, CostOverrunStats, FloodControlProject, RegionalStats, MIN_CONTRACTOR_PROJECTS, TOP_CONTRACTORS,
+};
+
+pub const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_PER_PAGE: usize = 50;
+const MAX_PER_PAGE: usize = 500;
+//connections answered at once; later ones wait in the queue
+const WORKERS: usize = 4;
+//a client that stops sending or reading is dropped after this long
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Response {
+    status: u16,
+    body: String,
+}
+
+struct Query {
+    params: HashMap<String, String>,
+}
+
+impl Query {
+    fn parse(query_string: &str) -> Query {
+        let params = query_string
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode_component(key), decode_component(value))
+            })
+            .collect();
+        Query { params }
+    }
+
+    fn text(&self, key: &str) -> Option<&str> {
+        self.params.get(key).map(|v| v.trim()).filter(|v| !v.is_empty())
+    }
+
+    fn number<T: FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        match self.text(key) {
+            Some(value) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| format!("Invalid value for '{}': {}", key, value)),
+            None => Ok(None),
+        }
+    }
+
+    fn matches(&self, key: &str, actual: &str) -> bool {
+        match self.text(key) {
+            Some(expected) => expected.eq_ignore_ascii_case(actual.trim()),
+            None => true,
+        }
+    }
+
+    //?sort=Column:desc,Column overrides the configured order for one request
+    fn sort_keys<T: Sortable>(&self, configured: &[SortKey]) -> Result<Vec<SortKey>, String> {
+        match self.text("sort") {
+            Some(text) => ordering::parse_keys::<T>(text),
+            None => Ok(configured.to_vec()),
+        }
+    }
+}
+
+pub fn serve(projects: &[FloodControlProject], port: u16, sorting: &SortConfig) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+
+    println!("✓ Serving {} projects on http://127.0.0.1:{}", projects.len(), port);
+    println!("  Endpoints: /summary, /regions, /contractors, /cost-trends, /projects");
+    println!("  Press Ctrl+C to stop the server.");
+
+    let (sender, receiver) = mpsc::channel::<TcpStream>();
+    let receiver = Mutex::new(receiver);
+
+    thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| loop {
+                //the lock is held only while taking the next connection
+                let next = receiver.lock().map(|receiver| receiver.recv());
+                let Ok(Ok(stream)) = next else { break };
+                if let Err(e) = handle_connection(stream, projects, sorting) {
+                    println!("⚠ Request failed: {}", e);
+                }
+            });
+        }
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if sender.send(stream).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => println!("⚠ Connection failed: {}", e),
+            }
+        }
+        drop(sender);
+    });
+
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, projects: &[FloodControlProject], sorting: &SortConfig) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    //headers are not used, but must be consumed before answering
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("/");
+
+    let response = if method == "GET" {
+        route(target, projects, sorting)
+    } else {
+        error_response(405, "Only GET requests are supported")
+    };
+
+    write_response(&mut stream, &response)
+}
+
+fn route(target: &str, projects: &[FloodControlProject], sorting: &SortConfig) -> Response {
+    let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+    let query = Query::parse(query_string);
+
+    let result = match path.trim_end_matches('/') {
+        "/summary" => Ok(json!(compute_summary(projects))),
+        "/regions" => regions_endpoint(projects, &query, sorting),
+        "/contractors" => contractors_endpoint(projects, &query, sorting),
+        "/cost-trends" => cost_trends_endpoint(projects, &query, sorting),
+        "/projects" => projects_endpoint(projects, &query),
+        _ => return error_response(404, &format!("Unknown endpoint: {}", path)),
+    };
+
+    match result {
+        Ok(value) => Response { status: 200, body: value.to_string() },
+        Err(message) => error_response(400, &message),
+    }
+}
+
+fn regions_endpoint(projects: &[FloodControlProject], query: &Query, sorting: &SortConfig) -> Result<Value, String> {
+    let keys = query.sort_keys::<RegionalStats>(&sorting.regional)?;
+    let regions: Vec<_> = build_regional_report(projects, &keys)
+        .into_iter()
+        .filter(|r| query.matches("region", &r.region) && query.matches("main_island", &r.main_island))
+        .collect();
+
+    paginate(regions, query)
+}
+
+fn contractors_endpoint(projects: &[FloodControlProject], query: &Query, sorting: &SortConfig) -> Result<Value, String> {
+    let min_projects = query.number::<u32>("min_projects")?.unwrap_or(MIN_CONTRACTOR_PROJECTS);
+    let top = query.number::<usize>("top")?.unwrap_or(TOP_CONTRACTORS);
+    let keys = query.sort_keys::<ContractorStats>(&sorting.contractors)?;
+
+    //filtered before the cut to `top`, so the list holds the top contractors with that flag
+    let contractors: Vec<_> = build_contractor_report(projects, min_projects, usize::MAX, &keys)
+        .into_iter()
+        .filter(|c| query.matches("risk_flag", &c.risk_flag))
+        .take(top)
+        .collect();
+
+    paginate(contractors, query)
+}
+
+fn cost_trends_endpoint(projects: &[FloodControlProject], query: &Query, sorting: &SortConfig) -> Result<Value, String> {
+    let year = query.number::<u32>("year")?;
+    let keys = query.sort_keys::<CostOverrunStats>(&sorting.trends)?;
+
+    let trends: Vec<_> = build_cost_report(projects, &keys)
+        .into_iter()
+        .filter(|c| year.is_none_or(|y| c.funding_year == y) && query.matches("type_of_work", &c.type_of_work))
+        .collect();
+
+    paginate(trends, query)
+}
+
+fn projects_endpoint(projects: &[FloodControlProject], query: &Query) -> Result<Value, String> {
+    let year = query.number::<u32>("year")?;
+
+    let matching: Vec<&FloodControlProject> = projects
+        .iter()
+        .filter(|p| {
+            year.is_none_or(|y| p.funding_year == y)
+                && query.matches("region", &p.region)
+                && query.matches("province", &p.province)
+                && query.matches("main_island", &p.main_island)
+                && query.matches("contractor", &p.contractor)
+                && query.matches("type_of_work", &p.type_of_work)
+        })
+        .collect();
+
+    paginate(matching, query)
+}
+
+fn paginate<T: Serialize>(items: Vec<T>, query: &Query) -> Result<Value, String> {
+    let page = query.number::<usize>("page")?.unwrap_or(1).max(1);
+    let per_page = query.number::<usize>("per_page")?.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let total = items.len();
+
+    let data: Vec<T> = items
+        .into_iter()
+        .skip((page - 1).saturating_mul(per_page))
+        .take(per_page)
+        .collect();
+
+    Ok(json!({
+        "total": total,
+        "page": page,
+        "per_page": per_page,
+        "data": data,
+    }))
+}
+
+fn error_response(status: u16, message: &str) -> Response {
+    Response { status, body: json!({ "error": message }).to_string() }
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+//decodes %XX escapes and '+' in query strings
+fn decode_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 3 <= bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_escapes_and_plus() {
+        assert_eq!(decode_component("Region+IV-A"), "Region IV-A");
+        assert_eq!(decode_component("A%26B%20C"), "A&B C");
+    }
+
+    #[test]
+    fn keeps_malformed_escapes() {
+        assert_eq!(decode_component("100%"), "100%");
+        assert_eq!(decode_component("%zz1"), "%zz1");
+        assert_eq!(decode_component("%4"), "%4");
+    }
+
+    #[test]
+    fn parses_query_parameters() {
+        let query = Query::parse("region=Region+V&year=2022&&empty=&flag");
+        assert_eq!(query.text("region"), Some("Region V"));
+        assert_eq!(query.number::<u32>("year"), Ok(Some(2022)));
+        assert_eq!(query.text("empty"), None);
+        assert_eq!(query.text("flag"), None);
+        assert!(query.matches("region", "region v"));
+        assert!(query.matches("province", "anything"));
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        let query = Query::parse("page=two");
+        assert!(query.number::<usize>("page").is_err());
+    }
+
+    #[test]
+    fn routes_errors_to_status_codes() {
+        let sorting = SortConfig::default();
+        assert_eq!(route("/unknown", &[], &sorting).status, 404);
+        assert_eq!(route("/regions?sort=Nope:desc", &[], &sorting).status, 400);
+        assert_eq!(route("/regions?sort=TotalBudget:sideways", &[], &sorting).status, 400);
+        assert_eq!(route("/contractors?top=-1", &[], &sorting).status, 400);
+        assert_eq!(route("/regions/?sort=TotalBudget:desc", &[], &sorting).status, 200);
+    }
+
+    #[test]
+    fn filters_contractors_by_risk_before_the_top_cut() {
+        //the larger contractor saves little and is flagged High; the smaller one is Low
+        let mut projects = vec![
+            FloodControlProject::sample("P1", "Region I", "Builder A"),
+            FloodControlProject::sample("P2", "Region I", "Builder B"),
+        ];
+        projects[1].contract_cost = 400_000.0;
+        projects[1].cost_savings = 600_000.0;
+
+        let response = route("/contractors?risk_flag=Low&top=1&min_projects=1", &projects, &SortConfig::default());
+        let body: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["total"], 1);
+        assert_eq!(body["data"][0]["contractor"], "Builder B");
+    }
+}
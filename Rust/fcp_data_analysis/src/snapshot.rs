@@ -0,0 +1,325 @@
+// Binary snapshot of the cleaned and deduplicated projects, stored in data/cache/.
+// A snapshot is only used when its format version, the build that wrote it, the hash
+// of the source file and the hash of the settings all match; otherwise the CSV is
+// parsed again and the snapshot rewritten. The build fingerprint changes with every
+// rebuild, so a change to the cleaning code can never be answered from an old snapshot.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::config::AnalysisConfig;
+use crate::intern::{Interner, Name};
+use crate::status::ProjectStatus;
+use crate::{data_path, FloodControlProject};
+
+const MAGIC: &[u8; 4] = b"FCPS";
+//layout of the header and records; what the records hold is covered by build_fingerprint
+const SNAPSHOT_VERSION: u32 = 8;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+//64-bit FNV-1a
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+//package version plus the size and modification time of the running executable, so any
+//rebuild invalidates older snapshots; None when the executable cannot be inspected
+fn build_fingerprint() -> Option<u64> {
+    let metadata = std::env::current_exe().and_then(std::fs::metadata).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?;
+    let build = format!("{}|{}|{}", env!("CARGO_PKG_VERSION"), metadata.len(), modified.as_nanos());
+    Some(fnv1a(build.as_bytes()))
+}
+
+//the whole config, defaults included, is hashed, so changing any setting also refreshes the snapshot
+fn config_hash(config: &AnalysisConfig) -> u64 {
+    fnv1a(serde_json::to_string(config).unwrap_or_default().as_bytes())
+}
+
+//one snapshot per source file, named after its absolute path
+fn snapshot_path(source: &Path) -> PathBuf {
+    let absolute = std::fs::canonicalize(source).unwrap_or_else(|_| source.to_path_buf());
+    data_path("cache").join(format!("snapshot_{:016x}.bin", fnv1a(absolute.to_string_lossy().as_bytes())))
+}
+
+fn write_u32(out: &mut impl Write, value: u32) -> std::io::Result<()> {
+    out.write_all(&value.to_le_bytes())
+}
+
+fn write_u64(out: &mut impl Write, value: u64) -> std::io::Result<()> {
+    out.write_all(&value.to_le_bytes())
+}
+
+fn write_str(out: &mut impl Write, value: &str) -> std::io::Result<()> {
+    write_u32(out, value.len() as u32)?;
+    out.write_all(value.as_bytes())
+}
+
+fn write_date(out: &mut impl Write, value: NaiveDate) -> std::io::Result<()> {
+    out.write_all(&value.num_days_from_ce().to_le_bytes())
+}
+
+//presence byte, then the date
+fn write_optional_date(out: &mut impl Write, value: Option<NaiveDate>) -> std::io::Result<()> {
+    match value {
+        Some(date) => {
+            out.write_all(&[1])?;
+            write_date(out, date)
+        },
+        None => out.write_all(&[0]),
+    }
+}
+
+fn read_array<const N: usize>(input: &mut impl Read) -> std::io::Result<[u8; N]> {
+    let mut buffer = [0u8; N];
+    input.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn read_u32(input: &mut impl Read) -> std::io::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(input)?))
+}
+
+fn read_u64(input: &mut impl Read) -> std::io::Result<u64> {
+    Ok(u64::from_le_bytes(read_array(input)?))
+}
+
+fn read_i64(input: &mut impl Read) -> std::io::Result<i64> {
+    Ok(i64::from_le_bytes(read_array(input)?))
+}
+
+fn read_f64(input: &mut impl Read) -> std::io::Result<f64> {
+    Ok(f64::from_le_bytes(read_array(input)?))
+}
+
+fn read_str(input: &mut impl Read) -> Result<String, Box<dyn Error>> {
+    let len = read_u32(input)? as usize;
+    let mut buffer = vec![0u8; len];
+    input.read_exact(&mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+fn read_name(input: &mut impl Read, interner: &mut Interner) -> Result<Name, Box<dyn Error>> {
+    Ok(interner.intern(&read_str(input)?))
+}
+
+fn read_date(input: &mut impl Read) -> Result<NaiveDate, Box<dyn Error>> {
+    let days = i32::from_le_bytes(read_array(input)?);
+    NaiveDate::from_num_days_from_ce_opt(days).ok_or_else(|| "invalid date in snapshot".into())
+}
+
+fn read_optional_date(input: &mut impl Read) -> Result<Option<NaiveDate>, Box<dyn Error>> {
+    match read_array::<1>(input)? {
+        [0] => Ok(None),
+        _ => Ok(Some(read_date(input)?)),
+    }
+}
+
+fn write_project(out: &mut impl Write, project: &FloodControlProject) -> std::io::Result<()> {
+    write_str(out, &project.main_island)?;
+    write_str(out, &project.region)?;
+    write_str(out, &project.province)?;
+    write_str(out, &project.project_id)?;
+    write_str(out, &project.project_name)?;
+    write_str(out, &project.type_of_work)?;
+    write_u32(out, project.funding_year)?;
+    write_str(out, &project.contract_id)?;
+    out.write_all(&project.approved_budget.to_le_bytes())?;
+    out.write_all(&project.contract_cost.to_le_bytes())?;
+    write_optional_date(out, project.actual_completion_date)?;
+    write_date(out, project.start_date)?;
+    write_str(out, &project.contractor)?;
+    out.write_all(&project.cost_savings.to_le_bytes())?;
+    out.write_all(&project.planned_duration_days.to_le_bytes())
+}
+
+fn read_project(input: &mut impl Read, interner: &mut Interner) -> Result<FloodControlProject, Box<dyn Error>> {
+    //fields are read in the order write_project wrote them
+    let main_island = read_name(input, interner)?;
+    let region = read_name(input, interner)?;
+    let province = read_name(input, interner)?;
+    let project_id = read_str(input)?;
+    let project_name = read_str(input)?;
+    let type_of_work = read_name(input, interner)?;
+    let funding_year = read_u32(input)?;
+    let contract_id = read_str(input)?;
+    let approved_budget = read_f64(input)?;
+    let contract_cost = read_f64(input)?;
+    let actual_completion_date = read_optional_date(input)?;
+    let start_date = read_date(input)?;
+    let contractor = read_name(input, interner)?;
+    let cost_savings = read_f64(input)?;
+    let planned_duration_days = read_i64(input)?;
+    let duration_days = actual_completion_date.map(|end| (end - start_date).num_days());
+
+    Ok(FloodControlProject {
+        main_island,
+        region,
+        province,
+        project_id,
+        project_name,
+        type_of_work,
+        funding_year,
+        contract_id,
+        approved_budget,
+        contract_cost,
+        actual_completion_date,
+        start_date,
+        contractor,
+        cost_savings,
+        duration_days,
+        planned_duration_days,
+        delay_days: duration_days.map(|days| days - planned_duration_days),
+        status: ProjectStatus::classify(start_date, actual_completion_date),
+    })
+}
+
+//what a snapshot was made from; all of it must match for the snapshot to be used
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SnapshotKey {
+    build: u64,
+    source: u64,
+    config: u64,
+}
+
+impl SnapshotKey {
+    fn new(source_bytes: &[u8], config: &AnalysisConfig) -> Option<SnapshotKey> {
+        Some(SnapshotKey { build: build_fingerprint()?, source: fnv1a(source_bytes), config: config_hash(config) })
+    }
+}
+
+//Ok(None) when there is no snapshot or it was made from another file, build, version or config
+fn read_snapshot(path: &Path, key: SnapshotKey) -> Result<Option<Vec<FloodControlProject>>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let mut input = BufReader::new(File::open(path)?);
+    let magic: [u8; 4] = read_array(&mut input)?;
+    if &magic != MAGIC
+        || read_u32(&mut input)? != SNAPSHOT_VERSION
+        || read_u64(&mut input)? != key.build
+        || read_u64(&mut input)? != key.source
+        || read_u64(&mut input)? != key.config
+    {
+        return Ok(None);
+    }
+
+    let count = read_u64(&mut input)? as usize;
+    let mut interner = Interner::default();
+    let mut projects: Vec<FloodControlProject> = Vec::with_capacity(count);
+    for _ in 0..count {
+        projects.push(read_project(&mut input, &mut interner)?);
+    }
+    Ok(Some(projects))
+}
+
+fn write_snapshot(path: &Path, key: SnapshotKey, projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    //write to a temporary file first so an interrupted run never leaves a truncated snapshot
+    let temp_path = path.with_extension("tmp");
+    let mut out = BufWriter::new(File::create(&temp_path)?);
+    out.write_all(MAGIC)?;
+    write_u32(&mut out, SNAPSHOT_VERSION)?;
+    write_u64(&mut out, key.build)?;
+    write_u64(&mut out, key.source)?;
+    write_u64(&mut out, key.config)?;
+    write_u64(&mut out, projects.len() as u64)?;
+    for project in projects {
+        write_project(&mut out, project)?;
+    }
+    out.flush()?;
+    drop(out);
+
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+//cached projects for this source file contents and config, if a valid snapshot exists
+pub fn load(source: &Path, source_bytes: &[u8], config: &AnalysisConfig) -> Option<Vec<FloodControlProject>> {
+    let path = snapshot_path(source);
+    match read_snapshot(&path, SnapshotKey::new(source_bytes, config)?) {
+        Ok(Some(projects)) => {
+            println!("✓ Loaded {} projects from snapshot data/cache/{}", projects.len(),
+                path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default());
+            println!("  Data quality, reference and duplicate reports skipped: they are produced when the CSV is parsed");
+            println!("  (the CSVs in data/ are from the run that wrote the snapshot; set cache.enabled to false to redo them)");
+            Some(projects)
+        },
+        Ok(None) => None,
+        Err(e) => {
+            println!("⚠ Ignoring unreadable snapshot {}: {}", path.display(), e);
+            None
+        },
+    }
+}
+
+//a failed save only costs the speed-up, so it is reported but not returned as an error
+pub fn save(source: &Path, source_bytes: &[u8], config: &AnalysisConfig, projects: &[FloodControlProject]) {
+    let path = snapshot_path(source);
+    let Some(key) = SnapshotKey::new(source_bytes, config) else { return };
+    if let Err(e) = write_snapshot(&path, key, projects) {
+        println!("⚠ Could not write snapshot {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_snapshot(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fcp_snapshot_test_{}_{}.bin", std::process::id(), name))
+    }
+
+    const KEY: SnapshotKey = SnapshotKey { build: 1, source: 2, config: 3 };
+
+    #[test]
+    fn round_trips_projects() {
+        let path = temp_snapshot("round_trip");
+        let mut ongoing = FloodControlProject::sample("P2", "Region II", "Builder B");
+        ongoing.actual_completion_date = None;
+        ongoing.duration_days = None;
+        ongoing.delay_days = None;
+        ongoing.status = ProjectStatus::classify(ongoing.start_date, None);
+        let projects = vec![FloodControlProject::sample("P1", "Region I", "Builder A"), ongoing];
+
+        write_snapshot(&path, KEY, &projects).unwrap();
+        let loaded = read_snapshot(&path, KEY).unwrap().unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].project_id, "P1");
+        assert_eq!(loaded[0].delay_days, Some(0));
+        assert_eq!(loaded[1].actual_completion_date, None);
+        assert_eq!(loaded[1].status, ProjectStatus::Ongoing);
+    }
+
+    #[test]
+    fn ignores_snapshots_from_another_build_source_or_config() {
+        let path = temp_snapshot("mismatch");
+        write_snapshot(&path, KEY, &[FloodControlProject::sample("P1", "Region I", "Builder A")]).unwrap();
+        for other in [
+            SnapshotKey { build: 9, ..KEY },
+            SnapshotKey { source: 9, ..KEY },
+            SnapshotKey { config: 9, ..KEY },
+        ] {
+            assert!(read_snapshot(&path, other).unwrap().is_none());
+        }
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn fingerprints_the_running_build() {
+        assert!(build_fingerprint().is_some());
+        assert_eq!(fnv1a(b""), FNV_OFFSET);
+    }
+}
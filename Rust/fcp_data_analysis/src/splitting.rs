@@ -0,0 +1,373 @@
+// Contract splitting detection: several contracts of one contractor, in one province
+// and type of work, that start together or share a name apart from numbered suffixes
+// (allowing typos and reordered words), each below an approval threshold while their
+// combined cost reaches it.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use csv::Writer;
+
+use crate::config::SplittingConfig;
+use crate::dedup::{self, UnionFind};
+use crate::{data_path, format_currency, FloodControlProject};
+
+//words that introduce the number of a part of a larger work ("Phase 2", "Lot No. 3")
+const SUFFIX_WORDS: &[&str] = &["phase", "package", "pkg", "lot", "section", "sec", "part", "stage", "segment", "contract"];
+const ROMAN_NUMERALS: &[&str] = &["i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x"];
+
+struct SplitCluster<'a> {
+    contractor: &'a str,
+    province: &'a str,
+    type_of_work: &'a str,
+    basis: Vec<&'static str>,
+    members: Vec<&'a FloodControlProject>,
+    combined_cost: f64,
+    largest_contract: f64,
+    threshold: f64,
+}
+
+fn is_part_number(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_digit()) || ROMAN_NUMERALS.contains(&word) || word.chars().count() == 1
+}
+
+//removes one trailing part marker; false when the name does not end in one
+fn strip_marker(words: &mut Vec<&str>) -> bool {
+    let Some(&number) = words.last() else { return false };
+    if !is_part_number(number) {
+        return false;
+    }
+
+    let before = &words[..words.len() - 1];
+    if before.last() == Some(&"no") && before.len() >= 2 && SUFFIX_WORDS.contains(&before[before.len() - 2]) {
+        words.truncate(words.len() - 3);
+    } else if before.last().is_some_and(|word| SUFFIX_WORDS.contains(word)) {
+        words.truncate(words.len() - 2);
+    } else if number.chars().all(|c| c.is_ascii_digit()) {
+        return false; //a bare number names the work, e.g. "River 77"
+    } else {
+        words.pop();
+    }
+    true
+}
+
+//project name without its trailing part markers: "Phase 2", "Package No. III", "Lot A", or a
+//final Roman numeral or letter, so "Phase 1 Package 2" goes as a whole. Other numbers stay,
+//so "River 77 Phase 2" and "River 191 Phase 1" keep different stems. None when the name has no such marker
+fn name_stem(name: &str) -> Option<String> {
+    let lowered = name.to_lowercase();
+    let mut words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    if !strip_marker(&mut words) {
+        return None;
+    }
+    while strip_marker(&mut words) {}
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
+}
+
+//a name stem as compared between projects: its words in order and sorted, so reordered names still compare close
+struct Stem {
+    ordered: Vec<char>,
+    sorted: Vec<char>,
+    numbers: Vec<String>,
+    funding_year: u32,
+}
+
+fn project_stem(project: &FloodControlProject) -> Option<Stem> {
+    let stem = name_stem(&project.project_name)?;
+    let mut words: Vec<&str> = stem.split(' ').collect();
+    words.sort_unstable();
+    let ordered: Vec<char> = stem.chars().collect();
+
+    Some(Stem {
+        sorted: words.join(" ").chars().collect(),
+        numbers: dedup::name_numbers(&ordered),
+        ordered,
+        funding_year: project.funding_year,
+    })
+}
+
+//numbered parts of the same work, procured in the same funding year: the stems differ only by
+//typos or word order, and carry the same numbers
+fn same_work(a: &Stem, b: &Stem, min_similarity: f64) -> bool {
+    a.funding_year == b.funding_year
+        && a.numbers == b.numbers
+        && (dedup::name_similarity(&a.ordered, &b.ordered, min_similarity).is_some()
+            || dedup::name_similarity(&a.sorted, &b.sorted, min_similarity).is_some())
+}
+
+fn cluster_group<'a>(members: &[&'a FloodControlProject], config: &SplittingConfig) -> Vec<Vec<&'a FloodControlProject>> {
+    let mut sorted: Vec<&FloodControlProject> = members.to_vec();
+    sorted.sort_by(|a, b| a.start_date.cmp(&b.start_date).then(a.project_id.cmp(&b.project_id)));
+
+    let mut union_find = UnionFind::new(sorted.len());
+
+    //overlapping starts: projects starting within the window of the first start in the window,
+    //so a run of starts a few days apart never stretches one cluster past the window
+    let mut window_first = 0;
+    for i in 1..sorted.len() {
+        if (sorted[i].start_date - sorted[window_first].start_date).num_days() <= config.start_date_window_days {
+            union_find.union(window_first, i);
+        } else {
+            window_first = i;
+        }
+    }
+
+    let stems: Vec<(usize, Stem)> = sorted.iter().enumerate().filter_map(|(i, p)| project_stem(p).map(|stem| (i, stem))).collect();
+    for (position, (a, stem_a)) in stems.iter().enumerate() {
+        for (b, stem_b) in &stems[position + 1..] {
+            if same_work(stem_a, stem_b, config.stem_similarity) {
+                union_find.union(*a, *b);
+            }
+        }
+    }
+
+    union_find
+        .groups()
+        .into_iter()
+        .filter(|group| group.len() >= config.min_cluster_size)
+        .map(|group| group.into_iter().map(|i| sorted[i]).collect())
+        .collect()
+}
+
+fn cluster_basis(members: &[&FloodControlProject], config: &SplittingConfig) -> Vec<&'static str> {
+    let mut basis: Vec<&'static str> = Vec::new();
+
+    if members.windows(2).any(|w| (w[1].start_date - w[0].start_date).num_days() <= config.start_date_window_days) {
+        basis.push("OverlappingStart");
+    }
+
+    let stems: Vec<Stem> = members.iter().filter_map(|p| project_stem(p)).collect();
+    let shares_stem = stems
+        .iter()
+        .enumerate()
+        .any(|(position, a)| stems[position + 1..].iter().any(|b| same_work(a, b, config.stem_similarity)));
+    if shares_stem {
+        basis.push("NumberedNameSuffix");
+    }
+
+    basis
+}
+
+fn detect_splitting<'a>(projects: &'a [FloodControlProject], config: &SplittingConfig) -> Vec<SplitCluster<'a>> {
+    let mut groups: BTreeMap<(&str, &str, &str), Vec<&FloodControlProject>> = BTreeMap::new();
+    // (contractor, province, type_of_work): projects
+    for project in projects {
+        groups.entry((&project.contractor, &project.province, &project.type_of_work)).or_default().push(project);
+    }
+
+    let mut clusters: Vec<SplitCluster> = Vec::new();
+
+    for ((contractor, province, type_of_work), members) in &groups {
+        for cluster in cluster_group(members, config) {
+            let combined_cost: f64 = cluster.iter().map(|p| p.contract_cost).sum();
+            let largest_contract = cluster.iter().map(|p| p.contract_cost).fold(0.0, f64::max);
+
+            //highest threshold that no single contract reaches but the cluster does
+            let crossed = config
+                .thresholds
+                .iter()
+                .copied()
+                .filter(|t| largest_contract < *t && combined_cost >= *t)
+                .fold(None, |best: Option<f64>, t| Some(best.map_or(t, |b| b.max(t))));
+
+            if let Some(threshold) = crossed {
+                clusters.push(SplitCluster {
+                    contractor,
+                    province,
+                    type_of_work,
+                    basis: cluster_basis(&cluster, config),
+                    members: cluster,
+                    combined_cost,
+                    largest_contract,
+                    threshold,
+                });
+            }
+        }
+    }
+
+    clusters
+}
+
+fn display_splitting_report(clusters: &[SplitCluster], config: &SplittingConfig) {
+    println!("\nContract Splitting Detection");
+    println!("(Same contractor, province and type of work; starts within {} days or numbered name suffixes; thresholds: {})",
+        config.start_date_window_days,
+        config.thresholds.iter().map(|t| format_currency(*t)).collect::<Vec<_>>().join(", "));
+    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<40} | {:<18} | {:<35} | {:>9} | {:>17} | {:>17} | {:>17} |",
+             "Contractor", "Province", "TypeOfWork", "Contracts", "CombinedCost", "LargestContract", "Threshold");
+    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for cluster in clusters {
+        let contractor: String = cluster.contractor.chars().take(40).collect();
+        let province: String = cluster.province.chars().take(18).collect();
+        let type_of_work: String = cluster.type_of_work.chars().take(35).collect();
+
+        println!("| {:<40} | {:<18} | {:<35} | {:>9} | {:>17} | {:>17} | {:>17} |",
+            contractor,
+            province,
+            type_of_work,
+            cluster.members.len(),
+            format_currency(cluster.combined_cost),
+            format_currency(cluster.largest_contract),
+            format_currency(cluster.threshold)
+        );
+    }
+    println!("--------------------------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("{} suspected split clusters.", clusters.len());
+}
+
+fn export_splitting_csv(clusters: &[SplitCluster], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["ClusterId", "Contractor", "Province", "TypeOfWork", "Basis", "ContractCount", "CombinedCost",
+        "LargestContract", "ThresholdCrossed", "FirstStartDate", "LastStartDate", "ProjectIds"])?;
+
+    //format and write data
+    for (index, cluster) in clusters.iter().enumerate() {
+        let first_start = cluster.members.iter().map(|p| p.start_date).min();
+        let last_start = cluster.members.iter().map(|p| p.start_date).max();
+
+        writer.write_record([
+            &(index + 1).to_string(),
+            cluster.contractor,
+            cluster.province,
+            cluster.type_of_work,
+            &cluster.basis.join("; "),
+            &cluster.members.len().to_string(),
+            &format_currency(cluster.combined_cost),
+            &format_currency(cluster.largest_contract),
+            &format_currency(cluster.threshold),
+            &first_start.map(|d| d.to_string()).unwrap_or_default(),
+            &last_start.map(|d| d.to_string()).unwrap_or_default(),
+            &cluster.members.iter().map(|p| p.project_id.as_str()).collect::<Vec<_>>().join("; "),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn splitting_report(projects: &[FloodControlProject], config: &SplittingConfig) -> Result<(), Box<dyn Error>> {
+    let clusters = detect_splitting(projects, config);
+
+    display_splitting_report(&clusters, config);
+    export_splitting_csv(&clusters, "report_contract_splitting.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn strips_trailing_part_markers() {
+        assert_eq!(name_stem("Flood Wall along River 77 Phase 2").as_deref(), Some("flood wall along river 77"));
+        assert_eq!(name_stem("Dike Package No. III").as_deref(), Some("dike"));
+        assert_eq!(name_stem("Revetment Lot A").as_deref(), Some("revetment"));
+        assert_eq!(name_stem("Revetment II").as_deref(), Some("revetment"));
+        assert_eq!(name_stem("Revetment Phase 2 Phase 3").as_deref(), Some("revetment"));
+        assert_eq!(name_stem("Seawall Phase 1 Package No. 2").as_deref(), Some("seawall"));
+        assert_eq!(name_stem("River 77 Phase 1 Lot B").as_deref(), Some("river 77"));
+    }
+
+    #[test]
+    fn keeps_numbers_that_name_the_work() {
+        assert_eq!(name_stem("Flood Wall along River 77"), None);
+        assert_eq!(name_stem("Drainage Canal"), None);
+        assert_eq!(name_stem("Phase 2"), None);
+    }
+
+    #[test]
+    fn different_rivers_are_not_grouped() {
+        let config = SplittingConfig { start_date_window_days: 0, ..SplittingConfig::default() };
+        let mut projects = Vec::new();
+        for (id, name, month) in [("P1", "River 77 Phase 2", 1), ("P2", "River 191 Phase 1", 5), ("P3", "River 193 Phase 2", 9)] {
+            let mut project = FloodControlProject::sample(id, "Region XIII", "CONTRACTOR A");
+            project.project_name = name.to_string();
+            project.start_date = NaiveDate::from_ymd_opt(2022, month, 1).unwrap();
+            projects.push(project);
+        }
+        let members: Vec<&FloodControlProject> = projects.iter().collect();
+        assert!(cluster_group(&members, &config).is_empty());
+
+        projects[1].project_name = "River 77 Phase 1".to_string();
+        let members: Vec<&FloodControlProject> = projects.iter().collect();
+        let clusters = cluster_group(&members, &config);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].len(), 2);
+    }
+
+    //projects of one contractor starting months apart, so only their names can group them
+    fn named(names: &[&str]) -> Vec<FloodControlProject> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let mut project = FloodControlProject::sample(&format!("P{}", i + 1), "Region I", "Builder A");
+                project.project_name = name.to_string();
+                project.start_date = NaiveDate::from_ymd_opt(2022, 1 + 4 * i as u32, 1).unwrap();
+                project
+            })
+            .collect()
+    }
+
+    fn cluster_sizes(projects: &[FloodControlProject]) -> Vec<usize> {
+        let config = SplittingConfig { start_date_window_days: 0, ..SplittingConfig::default() };
+        let members: Vec<&FloodControlProject> = projects.iter().collect();
+        cluster_group(&members, &config).iter().map(|cluster| cluster.len()).collect()
+    }
+
+    #[test]
+    fn groups_stems_with_a_typo() {
+        let projects = named(&["Slope Protection at Barangay Malinao Phase 1", "Slope Protecton at Barangay Malinao Phase 2"]);
+        assert_eq!(cluster_sizes(&projects), vec![2]);
+        let members: Vec<&FloodControlProject> = projects.iter().collect();
+        assert_eq!(cluster_basis(&members, &SplittingConfig::default()), vec!["NumberedNameSuffix"]);
+    }
+
+    #[test]
+    fn groups_stems_with_reordered_words() {
+        let projects = named(&["Slope Protection Barangay Malinao Phase 1", "Barangay Malinao Slope Protection Phase 2"]);
+        assert_eq!(cluster_sizes(&projects), vec![2]);
+    }
+
+    #[test]
+    fn groups_names_with_two_markers() {
+        let projects = named(&["Seawall Phase 1 Package 2", "Seawall Phase 1 Package 3", "Seawall Phase 2"]);
+        assert_eq!(cluster_sizes(&projects), vec![3]);
+    }
+
+    #[test]
+    fn start_windows_do_not_chain() {
+        let config = SplittingConfig::default();
+        //a start every 20 days for a year, all with unrelated names
+        let projects: Vec<FloodControlProject> = (0..18)
+            .map(|i| {
+                let mut project = FloodControlProject::sample(&format!("P{:02}", i), "Region I", "Builder A");
+                project.project_name = format!("Unrelated Work {}", i);
+                project.start_date = NaiveDate::from_ymd_opt(2022, 1, 1).unwrap() + chrono::Duration::days(20 * i);
+                project
+            })
+            .collect();
+        let members: Vec<&FloodControlProject> = projects.iter().collect();
+
+        for cluster in cluster_group(&members, &config) {
+            let first = cluster.iter().map(|p| p.start_date).min().unwrap();
+            let last = cluster.iter().map(|p| p.start_date).max().unwrap();
+            assert!((last - first).num_days() <= config.start_date_window_days);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Descriptive statistics for skewed peso amounts and delays.
+// All functions take unsorted values; NaN values are ignored.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Distribution {
+    pub count: usize,
+    pub min: f64,
+    pub p10: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub iqr: f64,
+    pub skewness: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+}
+
+pub fn sorted_values(values: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted
+}
+
+//linear interpolation between closest ranks, p in [0, 100]; expects sorted input
+pub fn percentile_sorted(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+pub fn percentile(values: &[f64], p: f64) -> f64 {
+    percentile_sorted(&sorted_values(values), p)
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    percentile(values, 50.0)
+}
+
+//median absolute deviation from the median
+pub fn mad(values: &[f64]) -> f64 {
+    let center = median(values);
+    let deviations: Vec<f64> = values.iter().filter(|v| !v.is_nan()).map(|v| (v - center).abs()).collect();
+    median(&deviations)
+}
+
+//Tukey fences (Q1 - k*IQR, Q3 + k*IQR)
+pub fn iqr_fences(values: &[f64], k: f64) -> (f64, f64) {
+    let sorted = sorted_values(values);
+    let q1 = percentile_sorted(&sorted, 25.0);
+    let q3 = percentile_sorted(&sorted, 75.0);
+    let iqr = q3 - q1;
+    (q1 - k * iqr, q3 + k * iqr)
+}
+
+pub fn mean(values: &[f64]) -> f64 {
+    let valid: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+    if valid.is_empty() {
+        return 0.0;
+    }
+    valid.iter().sum::<f64>() / valid.len() as f64
+}
+
+//sample standard deviation (n - 1)
+pub fn std_dev(values: &[f64]) -> f64 {
+    let valid: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+    if valid.len() < 2 {
+        return 0.0;
+    }
+
+    let avg = mean(&valid);
+    let variance = valid.iter().map(|v| (v - avg).powi(2)).sum::<f64>() / (valid.len() - 1) as f64;
+    variance.sqrt()
+}
+
+//sample skewness g1 = m3 / m2^1.5, 0 when the values have no spread
+pub fn skewness(values: &[f64]) -> f64 {
+    let valid: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+    if valid.len() < 3 {
+        return 0.0;
+    }
+
+    let n = valid.len() as f64;
+    let avg = mean(&valid);
+    let m2 = valid.iter().map(|v| (v - avg).powi(2)).sum::<f64>() / n;
+    let m3 = valid.iter().map(|v| (v - avg).powi(3)).sum::<f64>() / n;
+
+    if m2 > 0.0 { m3 / m2.powf(1.5) } else { 0.0 }
+}
+
+pub fn describe(values: &[f64]) -> Distribution {
+    let sorted = sorted_values(values);
+    let p25 = percentile_sorted(&sorted, 25.0);
+    let p75 = percentile_sorted(&sorted, 75.0);
+
+    Distribution {
+        count: sorted.len(),
+        min: sorted.first().copied().unwrap_or(0.0),
+        p10: percentile_sorted(&sorted, 10.0),
+        p25,
+        median: percentile_sorted(&sorted, 50.0),
+        p75,
+        p90: percentile_sorted(&sorted, 90.0),
+        p99: percentile_sorted(&sorted, 99.0),
+        max: sorted.last().copied().unwrap_or(0.0),
+        mean: mean(&sorted),
+        std_dev: std_dev(&sorted),
+        iqr: p75 - p25,
+        skewness: skewness(&sorted),
+    }
+}
+
+//equal-width bins between min and max; the last bin includes the max value
+pub fn histogram(values: &[f64], bins: usize) -> Vec<HistogramBin> {
+    let sorted = sorted_values(values);
+    if sorted.is_empty() || bins == 0 {
+        return Vec::new();
+    }
+
+    let low = sorted[0];
+    let high = sorted[sorted.len() - 1];
+    let width = (high - low) / bins as f64;
+
+    if width <= 0.0 {
+        return vec![HistogramBin { lower: low, upper: high, count: sorted.len() }];
+    }
+
+    let mut result: Vec<HistogramBin> = (0..bins)
+        .map(|i| HistogramBin {
+            lower: low + width * i as f64,
+            upper: if i + 1 == bins { high } else { low + width * (i + 1) as f64 },
+            count: 0,
+        })
+        .collect();
+
+    for value in &sorted {
+        let index = (((value - low) / width) as usize).min(bins - 1);
+        result[index].count += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_percentiles() {
+        let values = [4.0, 1.0, 3.0, 2.0];
+        assert_eq!(median(&values), 2.5);
+        assert_eq!(percentile(&values, 0.0), 1.0);
+        assert_eq!(percentile(&values, 100.0), 4.0);
+        assert_eq!(percentile(&values, 250.0), 4.0);
+    }
+
+    #[test]
+    fn ignores_nan_values() {
+        let values = [f64::NAN, 1.0, 3.0, f64::NAN];
+        assert_eq!(median(&values), 2.0);
+        assert_eq!(mean(&values), 2.0);
+        assert_eq!(describe(&values).count, 2);
+    }
+
+    #[test]
+    fn empty_and_constant_inputs_give_zero() {
+        assert_eq!(median(&[]), 0.0);
+        assert_eq!(median(&[f64::NAN]), 0.0);
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(std_dev(&[5.0]), 0.0);
+        assert_eq!(skewness(&[2.0, 2.0, 2.0]), 0.0);
+        assert_eq!(mad(&[7.0, 7.0, 7.0]), 0.0);
+    }
+
+    #[test]
+    fn measures_spread_and_fences() {
+        assert!((std_dev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]) - 2.138).abs() < 0.001);
+        assert_eq!(mad(&[1.0, 1.0, 2.0, 2.0, 4.0, 6.0, 9.0]), 1.0);
+        assert_eq!(iqr_fences(&[1.0, 2.0, 3.0, 4.0, 5.0], 1.5), (-1.0, 7.0));
+        assert!(skewness(&[1.0, 1.0, 1.0, 10.0]) > 0.0);
+    }
+
+    #[test]
+    fn histogram_puts_the_maximum_in_the_last_bin() {
+        let bins = histogram(&[0.0, 1.0, 2.0, 3.0, 4.0], 2);
+        assert_eq!(bins.len(), 2);
+        assert_eq!(bins[0].count + bins[1].count, 5);
+        assert_eq!(bins[1].upper, 4.0);
+        assert_eq!(bins[1].count, 3);
+
+        let single = histogram(&[3.0, 3.0], 4);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].count, 2);
+        assert!(histogram(&[], 4).is_empty());
+    }
+}
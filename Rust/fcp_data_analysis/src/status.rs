@@ -0,0 +1,180 @@
+// Project status derived from the start and completion dates, and the status report:
+// counts per status for each region and contractor, with the statuses that each
+// report metric is computed over.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use chrono::NaiveDate;
+use csv::Writer;
+use serde::Serialize;
+
+use crate::{data_path, FloodControlProject};
+
+//a timeline longer than this (from start to completion) is treated as a data error
+pub const MAX_PLAUSIBLE_DURATION_DAYS: i64 = 5 * 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum ProjectStatus {
+    Completed,
+    Ongoing,               //no completion date yet
+    CompletionBeforeStart, //completion date earlier than the start date
+    ImplausiblyLong,       //more than MAX_PLAUSIBLE_DURATION_DAYS from start to completion
+}
+
+//in declaration order, so a status can index its count
+pub const ALL_STATUSES: [ProjectStatus; 4] = [
+    ProjectStatus::Completed,
+    ProjectStatus::Ongoing,
+    ProjectStatus::CompletionBeforeStart,
+    ProjectStatus::ImplausiblyLong,
+];
+
+impl ProjectStatus {
+    pub fn classify(start: NaiveDate, completion: Option<NaiveDate>) -> ProjectStatus {
+        match completion.map(|end| (end - start).num_days()) {
+            None => ProjectStatus::Ongoing,
+            Some(days) if days < 0 => ProjectStatus::CompletionBeforeStart,
+            Some(days) if days > MAX_PLAUSIBLE_DURATION_DAYS => ProjectStatus::ImplausiblyLong,
+            Some(_) => ProjectStatus::Completed,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProjectStatus::Completed => "Completed",
+            ProjectStatus::Ongoing => "Ongoing",
+            ProjectStatus::CompletionBeforeStart => "CompletionBeforeStart",
+            ProjectStatus::ImplausiblyLong => "ImplausiblyLong",
+        }
+    }
+
+    //only completed projects with a plausible timeline have a delay worth averaging
+    pub fn counts_toward_delay(self) -> bool {
+        self == ProjectStatus::Completed
+    }
+}
+
+//(metrics, statuses they are computed over), by report column or summary.json field
+const METRIC_COVERAGE: &[(&str, &[ProjectStatus])] = &[
+    ("TotalBudget, MedianSavings (report 1)", &ALL_STATUSES),
+    ("AvgDuration, AvgDelay, HighDelay%, EfficiencyScore (report 1)", &[ProjectStatus::Completed]),
+    ("ProjectCount, TotalContractCost, TotalCostSavings (report 2)", &ALL_STATUSES),
+    ("AvgDurationDays, AvgCompletionDelayDays, ReliabilityIndex, RiskFlag (report 2)", &[ProjectStatus::Completed]),
+    ("TotalProjects, AvgCostSavings, OverrunRate, YoYChange, CAGR (report 3)", &ALL_STATUSES),
+    ("AvgDurationDays, AvgDelayDays (report 3)", &[ProjectStatus::Completed]),
+    ("total_projects, total_savings, total_budget (summary)", &ALL_STATUSES),
+    ("global_average_duration_days, global_average_delay_days (summary)", &[ProjectStatus::Completed]),
+];
+
+type StatusCounts = [u32; ALL_STATUSES.len()];
+
+fn count_by<F>(projects: &[FloodControlProject], key: F) -> BTreeMap<String, StatusCounts>
+where
+    F: Fn(&FloodControlProject) -> &str,
+{
+    let mut groups: BTreeMap<String, StatusCounts> = BTreeMap::new();
+    for project in projects {
+        groups.entry(key(project).to_string()).or_default()[project.status as usize] += 1;
+    }
+    groups
+}
+
+fn display_status_table(title: &str, groups: &BTreeMap<String, StatusCounts>) {
+    println!("\n{}", title);
+    println!("(Filtered: 2021-2023 Projects)");
+    println!("-------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<45} | {:>10} | {:>10} | {:>21} | {:>15} | {:>8} |",
+             "Group", "Completed", "Ongoing", "CompletionBeforeStart", "ImplausiblyLong", "Total");
+    println!("-------------------------------------------------------------------------------------------------------------------------------");
+
+    for (group, counts) in groups {
+        let name: String = group.chars().take(45).collect();
+        println!("| {:<45} | {:>10} | {:>10} | {:>21} | {:>15} | {:>8} |",
+            name, counts[0], counts[1], counts[2], counts[3], counts.iter().sum::<u32>());
+    }
+    println!("-------------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn display_metric_coverage() {
+    println!("\nStatuses included in each report metric");
+    for (metrics, statuses) in METRIC_COVERAGE {
+        let labels: Vec<&str> = statuses.iter().map(|s| s.label()).collect();
+        println!("- {}: {}", metrics, labels.join(", "));
+    }
+    println!("(ImplausiblyLong: more than {} days from start to completion; delay = duration minus planned duration)", MAX_PLAUSIBLE_DURATION_DAYS);
+}
+
+fn export_status_csv(tables: &[(&str, &BTreeMap<String, StatusCounts>)], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["GroupBy", "Group", "Completed", "Ongoing", "CompletionBeforeStart", "ImplausiblyLong", "Total"])?;
+
+    //format and write data
+    for (group_by, groups) in tables {
+        for (group, counts) in groups.iter() {
+            writer.write_record([
+                group_by.to_string(),
+                group.clone(),
+                counts[0].to_string(),
+                counts[1].to_string(),
+                counts[2].to_string(),
+                counts[3].to_string(),
+                counts.iter().sum::<u32>().to_string(),
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn status_report(projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    let by_region = count_by(projects, |p| &p.region);
+    let by_contractor = count_by(projects, |p| &p.contractor);
+
+    display_status_table("Project Status by Region", &by_region);
+    display_status_table("Project Status by Contractor", &by_contractor);
+    display_metric_coverage();
+    export_status_csv(&[("Region", &by_region), ("Contractor", &by_contractor)], "report_status.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_timelines() {
+        let start = NaiveDate::from_ymd_opt(2022, 3, 1).unwrap();
+        let days = |n: i64| Some(start + chrono::Duration::days(n));
+        assert_eq!(ProjectStatus::classify(start, None), ProjectStatus::Ongoing);
+        assert_eq!(ProjectStatus::classify(start, days(0)), ProjectStatus::Completed);
+        assert_eq!(ProjectStatus::classify(start, days(-1)), ProjectStatus::CompletionBeforeStart);
+        assert_eq!(ProjectStatus::classify(start, days(MAX_PLAUSIBLE_DURATION_DAYS)), ProjectStatus::Completed);
+        assert_eq!(ProjectStatus::classify(start, days(MAX_PLAUSIBLE_DURATION_DAYS + 1)), ProjectStatus::ImplausiblyLong);
+    }
+
+    #[test]
+    fn only_completed_projects_count_toward_delay() {
+        let counted: Vec<ProjectStatus> = ALL_STATUSES.into_iter().filter(|s| s.counts_toward_delay()).collect();
+        assert_eq!(counted, vec![ProjectStatus::Completed]);
+    }
+
+    #[test]
+    fn counts_index_by_status() {
+        let completed = FloodControlProject::sample("P1", "Region I", "Builder A");
+        let mut ongoing = FloodControlProject::sample("P2", "Region I", "Builder B");
+        ongoing.status = ProjectStatus::Ongoing;
+        let other = FloodControlProject::sample("P3", "Region II", "Builder A");
+
+        let by_region = count_by(&[completed, ongoing, other], |p| &p.region);
+        assert_eq!(by_region["Region I"], [1, 1, 0, 0]);
+        assert_eq!(by_region["Region II"], [1, 0, 0, 0]);
+        for (index, status) in ALL_STATUSES.into_iter().enumerate() {
+            assert_eq!(status as usize, index);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// Year-over-year and compound annual growth for any per-year metric.
+// Each point is compared with the nearest earlier year present for the same group,
+// so gaps and unsorted input are handled; a missing or zero baseline gives None ("N/A").
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct YearComparison {
+    //nearest earlier year with data, used as the YoY baseline
+    pub base_year: Option<u32>,
+    pub yoy_change: Option<f64>,
+    //first year of the group, used as the CAGR baseline
+    pub cagr_since: Option<u32>,
+    pub cagr: Option<f64>,
+}
+
+//percentage change; the baseline's magnitude is used so a rise is positive even from a negative base
+pub fn percent_change(previous: f64, current: f64) -> Option<f64> {
+    if !previous.is_finite() || !current.is_finite() || previous.abs() < f64::EPSILON {
+        return None;
+    }
+    Some(((current - previous) / previous.abs()) * 100.0)
+}
+
+//compound annual growth rate in percent; only defined for positive values over at least one year
+pub fn cagr(first_year: u32, first_value: f64, last_year: u32, last_value: f64) -> Option<f64> {
+    let defined = last_year > first_year && first_value > 0.0 && last_value > 0.0;
+    if !defined || !first_value.is_finite() || !last_value.is_finite() {
+        return None;
+    }
+    let years = (last_year - first_year) as f64;
+    Some(((last_value / first_value).powf(1.0 / years) - 1.0) * 100.0)
+}
+
+//points are (group, year, value); the result is aligned with the input order
+pub fn compare_by_year<K: Ord + Clone>(points: &[(K, u32, f64)]) -> Vec<YearComparison> {
+    let mut series: BTreeMap<K, BTreeMap<u32, f64>> = BTreeMap::new();
+    for (group, year, value) in points {
+        series.entry(group.clone()).or_default().insert(*year, *value);
+    }
+
+    points
+        .iter()
+        .map(|(group, year, value)| {
+            let years = &series[group];
+            let previous = years.range(..*year).next_back();
+            let first = years.iter().next().filter(|(first_year, _)| *first_year < year);
+
+            YearComparison {
+                base_year: previous.map(|(y, _)| *y),
+                yoy_change: previous.and_then(|(_, prev)| percent_change(*prev, *value)),
+                cagr_since: first.map(|(y, _)| *y),
+                cagr: first.and_then(|(first_year, first_value)| cagr(*first_year, *first_value, *year, *value)),
+            }
+        })
+        .collect()
+}
+
+pub fn format_change(change: Option<f64>) -> String {
+    match change {
+        Some(value) => format!("{:.2}", value),
+        None => "N/A".to_string(),
+    }
+}
+
+pub fn format_year(year: Option<u32>) -> String {
+    match year {
+        Some(value) => value.to_string(),
+        None => "N/A".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_change_needs_a_nonzero_baseline() {
+        assert_eq!(percent_change(100.0, 150.0), Some(50.0));
+        assert_eq!(percent_change(-100.0, -50.0), Some(50.0));
+        assert_eq!(percent_change(0.0, 10.0), None);
+        assert_eq!(percent_change(f64::NAN, 10.0), None);
+        assert_eq!(percent_change(10.0, f64::INFINITY), None);
+    }
+
+    #[test]
+    fn cagr_needs_positive_values_over_a_year() {
+        let growth = cagr(2021, 100.0, 2023, 121.0).unwrap();
+        assert!((growth - 10.0).abs() < 1e-9);
+        assert_eq!(cagr(2021, 100.0, 2021, 121.0), None);
+        assert_eq!(cagr(2021, 0.0, 2023, 121.0), None);
+        assert_eq!(cagr(2021, 100.0, 2023, -5.0), None);
+    }
+
+    #[test]
+    fn compares_with_the_nearest_earlier_year_per_group() {
+        //unsorted, with a gap in group "a" and a single year in group "b"
+        let points = [("a", 2023, 150.0), ("b", 2022, 10.0), ("a", 2021, 100.0)];
+        let compared = compare_by_year(&points);
+
+        assert_eq!(compared[0].base_year, Some(2021));
+        assert_eq!(compared[0].yoy_change, Some(50.0));
+        assert_eq!(compared[0].cagr_since, Some(2021));
+        assert!(compared[1].base_year.is_none() && compared[1].yoy_change.is_none() && compared[1].cagr.is_none());
+        assert!(compared[2].base_year.is_none());
+        assert_eq!(format_change(compared[2].yoy_change), "N/A");
+        assert_eq!(format_year(compared[0].base_year), "2021");
+    }
+}
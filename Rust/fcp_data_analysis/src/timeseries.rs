@@ -0,0 +1,316 @@
+// Monthly and quarterly time series by start date and by completion date,
+// plus projects in progress per period and calendar-month seasonality.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use chrono::{Datelike, NaiveDate};
+use csv::Writer;
+
+use crate::{data_path, format_currency, metric_delay_days, metric_duration_days, FloodControlProject};
+
+const MONTH_NAMES: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+#[derive(Clone, Copy)]
+enum Granularity {
+    Month,
+    Quarter,
+}
+
+impl Granularity {
+    //(year, month 1-12 or quarter 1-4)
+    fn key(self, date: NaiveDate) -> (i32, u32) {
+        match self {
+            Granularity::Month => (date.year(), date.month()),
+            Granularity::Quarter => (date.year(), (date.month() - 1) / 3 + 1),
+        }
+    }
+
+    fn next(self, (year, part): (i32, u32)) -> (i32, u32) {
+        let parts_per_year = match self {
+            Granularity::Month => 12,
+            Granularity::Quarter => 4,
+        };
+        if part == parts_per_year { (year + 1, 1) } else { (year, part + 1) }
+    }
+
+    fn label(self, (year, part): (i32, u32)) -> String {
+        match self {
+            Granularity::Month => format!("{}-{:02}", year, part),
+            Granularity::Quarter => format!("{}-Q{}", year, part),
+        }
+    }
+}
+
+#[derive(Default)]
+struct PeriodStats {
+    started: u32,
+    started_spend: f64,
+    started_savings: f64,
+    started_duration_days: i64,
+    started_delay_days: i64,
+    started_completed: u32, //started projects whose delay counts toward the average
+    completed: u32,
+    completed_spend: f64,
+    completed_duration_days: i64,
+    completed_delay_days: i64,
+    completed_with_delay: u32,
+    in_progress: u32,
+}
+
+fn average(total: i64, count: u32) -> f64 {
+    if count > 0 { total as f64 / count as f64 } else { 0.0 }
+}
+
+//as in the report's CSV header
+const COLUMNS: &[&str] = &["StartedProjects", "StartedSpend", "StartedSavings", "StartedAvgDuration", "StartedAvgDelay",
+    "CompletedProjects", "CompletedSpend", "CompletedAvgDuration", "CompletedAvgDelay", "InProgress"];
+
+impl PeriodStats {
+    fn value(&self, column: &str) -> f64 {
+        match column {
+            "StartedProjects" => self.started as f64,
+            "StartedSpend" => self.started_spend,
+            "StartedSavings" => self.started_savings,
+            "StartedAvgDuration" => average(self.started_duration_days, self.started_completed),
+            "StartedAvgDelay" => average(self.started_delay_days, self.started_completed),
+            "CompletedProjects" => self.completed as f64,
+            "CompletedSpend" => self.completed_spend,
+            "CompletedAvgDuration" => average(self.completed_duration_days, self.completed_with_delay),
+            "CompletedAvgDelay" => average(self.completed_delay_days, self.completed_with_delay),
+            _ => self.in_progress as f64,
+        }
+    }
+}
+
+//(quarter, calendar year, value) of one quarterly column for the growth report, so each
+//quarter is compared with the same quarter of earlier years
+pub fn quarter_points(projects: &[FloodControlProject], column: &str) -> Result<Vec<(String, u32, f64)>, String> {
+    if !COLUMNS.contains(&column) {
+        return Err(format!("Unknown time series metric {} (metrics: {})", column, COLUMNS.join(", ")));
+    }
+    Ok(aggregate_periods(projects, Granularity::Quarter)
+        .iter()
+        .map(|((year, quarter), stats)| (format!("Q{}", quarter), *year as u32, stats.value(column)))
+        .collect())
+}
+
+fn aggregate_periods(projects: &[FloodControlProject], granularity: Granularity) -> BTreeMap<(i32, u32), PeriodStats> {
+    let mut periods: BTreeMap<(i32, u32), PeriodStats> = BTreeMap::new();
+
+    //ongoing projects count as in progress up to the latest period in the data
+    let last_key = projects
+        .iter()
+        .flat_map(|p| [Some(p.start_date), p.actual_completion_date])
+        .flatten()
+        .map(|date| granularity.key(date))
+        .max();
+
+    for project in projects {
+        let start_key = granularity.key(project.start_date);
+
+        let started = periods.entry(start_key).or_default();
+        started.started += 1;
+        started.started_spend += project.contract_cost;
+        started.started_savings += project.cost_savings;
+        if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+            started.started_duration_days += duration_days;
+            started.started_delay_days += delay_days;
+            started.started_completed += 1;
+        }
+
+        let end_key = match project.actual_completion_date {
+            Some(end) => {
+                let end_key = granularity.key(end);
+                let completed = periods.entry(end_key).or_default();
+                completed.completed += 1;
+                completed.completed_spend += project.contract_cost;
+                if let (Some(duration_days), Some(delay_days)) = (metric_duration_days(project), metric_delay_days(project)) {
+                    completed.completed_duration_days += duration_days;
+                    completed.completed_delay_days += delay_days;
+                    completed.completed_with_delay += 1;
+                }
+                end_key
+            },
+            None => last_key.unwrap_or(start_key),
+        };
+
+        //active in every period from its start to its completion
+        let mut key = start_key;
+        while key <= end_key {
+            periods.entry(key).or_default().in_progress += 1;
+            key = granularity.next(key);
+        }
+    }
+
+    //fill gaps so every period in the range appears once
+    if let (Some(&first), Some(&last)) = (periods.keys().next(), periods.keys().next_back()) {
+        let mut key = first;
+        while key <= last {
+            periods.entry(key).or_default();
+            key = granularity.next(key);
+        }
+    }
+
+    periods
+}
+
+fn display_timeseries_report(title: &str, periods: &BTreeMap<(i32, u32), PeriodStats>, granularity: Granularity) {
+    println!("\n{}", title);
+    println!("(Filtered: 2021-2023 Projects; Started = by StartDate, Completed = by ActualCompletionDate)");
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+    println!("| {:<8} | {:>7} | {:>20} | {:>17} | {:>11} | {:>9} | {:>9} | {:>20} | {:>10} |",
+             "Period", "Started", "StartedSpend", "StartedSavings", "AvgDuration", "AvgDelay", "Completed", "CompletedSpend", "InProgress");
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+
+    for (key, stats) in periods {
+        println!("| {:<8} | {:>7} | {:>20} | {:>17} | {:>11.2} | {:>9.2} | {:>9} | {:>20} | {:>10} |",
+            granularity.label(*key),
+            stats.started,
+            format_currency(stats.started_spend),
+            format_currency(stats.started_savings),
+            average(stats.started_duration_days, stats.started_completed),
+            average(stats.started_delay_days, stats.started_completed),
+            stats.completed,
+            format_currency(stats.completed_spend),
+            stats.in_progress
+        );
+    }
+    println!("-------------------------------------------------------------------------------------------------------------------------------------------------");
+}
+
+fn export_timeseries_csv(periods: &BTreeMap<(i32, u32), PeriodStats>, granularity: Granularity, filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Period", "StartedProjects", "StartedSpend", "StartedSavings", "StartedAvgDuration", "StartedAvgDelay",
+        "CompletedProjects", "CompletedSpend", "CompletedAvgDuration", "CompletedAvgDelay", "InProgress"])?;
+
+    //format and write data
+    for (key, stats) in periods {
+        writer.write_record([
+            &granularity.label(*key),
+            &stats.started.to_string(),
+            &format_currency(stats.started_spend),
+            &format_currency(stats.started_savings),
+            &format!("{:.2}", average(stats.started_duration_days, stats.started_completed)),
+            &format!("{:.2}", average(stats.started_delay_days, stats.started_completed)),
+            &stats.completed.to_string(),
+            &format_currency(stats.completed_spend),
+            &format!("{:.2}", average(stats.completed_duration_days, stats.completed_with_delay)),
+            &format!("{:.2}", average(stats.completed_delay_days, stats.completed_with_delay)),
+            &stats.in_progress.to_string(),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+//calendar-month totals across all years, to show rainy-season and year-end bunching
+fn export_seasonality_csv(projects: &[FloodControlProject], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut started = [0u32; 12];
+    let mut started_spend = [0.0f64; 12];
+    let mut completed = [0u32; 12];
+    let total_spend: f64 = projects.iter().map(|p| p.contract_cost).sum();
+
+    for project in projects {
+        let start_month = project.start_date.month0() as usize;
+        started[start_month] += 1;
+        started_spend[start_month] += project.contract_cost;
+        if let Some(end) = project.actual_completion_date {
+            completed[end.month0() as usize] += 1;
+        }
+    }
+
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["Month", "StartedProjects", "StartedSpend", "ShareOfSpend%", "CompletedProjects"])?;
+
+    //format and write data
+    for month in 0..12 {
+        let share = if total_spend > 0.0 { (started_spend[month] / total_spend) * 100.0 } else { 0.0 };
+        writer.write_record([
+            MONTH_NAMES[month],
+            &started[month].to_string(),
+            &format_currency(started_spend[month]),
+            &format!("{:.2}", share),
+            &completed[month].to_string(),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+pub fn timeseries_report(projects: &[FloodControlProject]) -> Result<(), Box<dyn Error>> {
+    let monthly = aggregate_periods(projects, Granularity::Month);
+    let quarterly = aggregate_periods(projects, Granularity::Quarter);
+
+    display_timeseries_report("Quarterly Time Series", &quarterly, Granularity::Quarter);
+    export_timeseries_csv(&monthly, Granularity::Month, "report_timeseries_monthly.csv")?;
+    export_timeseries_csv(&quarterly, Granularity::Quarter, "report_timeseries_quarterly.csv")?;
+    export_seasonality_csv(projects, "report_timeseries_seasonality.csv")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periods_roll_over_the_year() {
+        let december = NaiveDate::from_ymd_opt(2022, 12, 15).unwrap();
+        assert_eq!(Granularity::Month.key(december), (2022, 12));
+        assert_eq!(Granularity::Quarter.key(december), (2022, 4));
+        assert_eq!(Granularity::Month.next((2022, 12)), (2023, 1));
+        assert_eq!(Granularity::Quarter.next((2022, 4)), (2023, 1));
+        assert_eq!(Granularity::Quarter.label((2023, 1)), "2023-Q1");
+        assert_eq!(Granularity::Month.label((2023, 1)), "2023-01");
+    }
+
+    #[test]
+    fn ongoing_projects_stay_in_progress_to_the_last_period() {
+        //completed: started 2022-03-01, completed 2022-06-09
+        let completed = FloodControlProject::sample("P1", "Region I", "Builder A");
+        let mut ongoing = FloodControlProject::sample("P2", "Region I", "Builder A");
+        ongoing.start_date = NaiveDate::from_ymd_opt(2022, 4, 10).unwrap();
+        ongoing.actual_completion_date = None;
+
+        let periods = aggregate_periods(&[completed, ongoing], Granularity::Month);
+        let in_progress: Vec<((i32, u32), u32)> = periods.iter().map(|(key, stats)| (*key, stats.in_progress)).collect();
+        assert_eq!(in_progress, vec![((2022, 3), 1), ((2022, 4), 2), ((2022, 5), 2), ((2022, 6), 2)]);
+        assert_eq!(periods[&(2022, 6)].completed, 1);
+        assert_eq!(periods[&(2022, 4)].started, 1);
+    }
+
+    #[test]
+    fn gaps_between_periods_are_filled() {
+        let first = FloodControlProject::sample("P1", "Region I", "Builder A");
+        let mut later = FloodControlProject::sample("P2", "Region I", "Builder A");
+        later.start_date = NaiveDate::from_ymd_opt(2023, 2, 1).unwrap();
+        later.actual_completion_date = NaiveDate::from_ymd_opt(2023, 3, 1);
+
+        let periods = aggregate_periods(&[first, later], Granularity::Quarter);
+        let keys: Vec<(i32, u32)> = periods.keys().copied().collect();
+        assert_eq!(keys, vec![(2022, 1), (2022, 2), (2022, 3), (2022, 4), (2023, 1)]);
+        assert_eq!(periods[&(2022, 3)].started + periods[&(2022, 3)].completed, 0);
+        assert_eq!(average(10, 0), 0.0);
+    }
+
+    #[test]
+    fn quarter_points_label_quarters_across_years() {
+        let first = FloodControlProject::sample("P1", "Region I", "Builder A");
+        let mut later = FloodControlProject::sample("P2", "Region I", "Builder A");
+        later.start_date = NaiveDate::from_ymd_opt(2023, 1, 20).unwrap();
+        later.actual_completion_date = None;
+
+        let points = quarter_points(&[first, later], "StartedProjects").unwrap();
+        assert_eq!(points.first(), Some(&("Q1".to_string(), 2022, 1.0)));
+        assert_eq!(points.last(), Some(&("Q1".to_string(), 2023, 1.0)));
+        assert!(quarter_points(&[], "Started").is_err());
+    }
+}
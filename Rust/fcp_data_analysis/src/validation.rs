@@ -0,0 +1,435 @@
+// Declarative row validation. The rules come from the validation section of
+// data/fcp_config.json; every failed rule is an issue with the rule's severity, and rows
+// with a drop issue are left out of the reports. Issues are tallied into a data quality
+// scorecard per column and per region, shown and exported whenever the CSV is parsed.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+use chrono::Datelike;
+use csv::Writer;
+
+use crate::config::{AnalysisConfig, Rule, Severity, ValidationConfig, CONFIG_FILENAME};
+use crate::geo::ReferenceCounts;
+use crate::intern::Name;
+use crate::{data_path, dates, geo, money, schema, FloodControlProject, RawFloodControlProject};
+
+//a row in the analysis window after the per-row rules; the region/province rule runs later, in file order
+pub struct ValidatedRow {
+    pub region: Name,
+    pub province: Name,
+    pub issues: Vec<Severity>, //one per failed rule check; the failing columns are tallied in the scorecard
+    pub project: Option<FloodControlProject>, //None when clean_project could not use the row
+}
+
+//rule checks per column, or rows per region, with the failures by severity
+#[derive(Debug, Default, Clone, Copy)]
+struct Tally {
+    checked: usize,
+    info: usize,
+    warn: usize,
+    drop: usize,
+}
+
+impl Tally {
+    fn add(&mut self, severity: Severity) {
+        match severity {
+            Severity::Info => self.info += 1,
+            Severity::Warn => self.warn += 1,
+            Severity::Drop => self.drop += 1,
+        }
+    }
+
+    fn merge(&mut self, other: Tally) {
+        self.checked += other.checked;
+        self.info += other.info;
+        self.warn += other.warn;
+        self.drop += other.drop;
+    }
+
+    //checks without a warn or drop failure
+    fn pass_pct(&self) -> f64 {
+        if self.checked > 0 {
+            (self.checked - self.warn - self.drop) as f64 / self.checked as f64 * 100.0
+        } else { 100.0 }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Scorecard {
+    columns: BTreeMap<String, Tally>,
+    regions: BTreeMap<Name, Tally>, //rows, counted under their most severe issue
+    pub reference: ReferenceCounts, //renames and mismatches against the bundled reference
+}
+
+impl Scorecard {
+    fn check(&mut self, column: &str, failed: Option<Severity>, issues: &mut Vec<Severity>) {
+        //the key is allocated once per column, not once per check
+        let tally = match self.columns.get_mut(column) {
+            Some(tally) => tally,
+            None => self.columns.entry(column.to_string()).or_default(),
+        };
+        tally.checked += 1;
+        if let Some(severity) = failed {
+            tally.add(severity);
+            issues.push(severity);
+        }
+    }
+
+    pub fn merge(&mut self, other: Scorecard) {
+        for (column, tally) in other.columns {
+            self.columns.entry(column).or_default().merge(tally);
+        }
+        for (region, tally) in other.regions {
+            self.regions.entry(region).or_default().merge(tally);
+        }
+        self.reference.merge(other.reference);
+    }
+
+    //records the row under its region and returns its project unless an issue drops it
+    pub fn accept(&mut self, row: ValidatedRow) -> Option<FloodControlProject> {
+        let worst = row.issues.iter().copied().max();
+        let tally = self.regions.entry(row.region).or_default();
+        tally.checked += 1;
+        if let Some(severity) = worst {
+            tally.add(severity);
+        }
+        if worst == Some(Severity::Drop) { None } else { row.project }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.regions.values().map(|tally| tally.checked).sum()
+    }
+
+    pub fn rows_with(&self, severity: Severity) -> usize {
+        self.regions.values().map(|tally| match severity {
+            Severity::Info => tally.info,
+            Severity::Warn => tally.warn,
+            Severity::Drop => tally.drop,
+        }).sum()
+    }
+}
+
+//the first region each province appeared under, in file order
+#[derive(Default)]
+pub struct ProvinceRegions {
+    first_region: HashMap<Name, Name>,
+}
+
+impl ProvinceRegions {
+    pub fn check(&mut self, row: &mut ValidatedRow, config: &ValidationConfig, scorecard: &mut Scorecard) {
+        for rule in &config.rules {
+            let Rule::RegionProvince { severity } = rule else { continue };
+            if row.province.trim().is_empty() || row.region.trim().is_empty() {
+                continue; //left to the required rule
+            }
+            let first = self.first_region.entry(row.province.clone()).or_insert_with(|| row.region.clone());
+            let failed = (*first != row.region).then_some(*severity);
+            scorecard.check("Province", failed, &mut row.issues);
+        }
+    }
+}
+
+fn raw_field<'a>(raw: &'a RawFloodControlProject, column: &str) -> Cow<'a, str> {
+    let value: &str = match column {
+        "MainIsland" => &raw.main_island,
+        "Region" => &raw.region,
+        "Province" => &raw.province,
+        "ProjectId" => &raw.project_id,
+        "ProjectName" => &raw.project_name,
+        "TypeOfWork" => &raw.type_of_work,
+        "FundingYear" => return Cow::Owned(raw.funding_year.map(|year| year.to_string()).unwrap_or_default()),
+        "ContractId" => &raw.contract_id,
+        "ApprovedBudgetForContract" => &raw.approved_budget_for_contract,
+        "ContractCost" => &raw.contract_cost,
+        "ActualCompletionDate" => &raw.actual_completion_date,
+        "Contractor" => &raw.contractor,
+        "StartDate" => &raw.start_date,
+        "ProjectLatitude" => &raw.project_latitude,
+        "ProjectLongitude" => &raw.project_longitude,
+        "ProvincialCapital" => &raw.provincial_capital,
+        "ProvincialCapitalLatitude" => &raw.provincial_capital_latitude,
+        "ProvincialCapitalLongitude" => &raw.provincial_capital_longitude,
+        "ContractDuration" => &raw.contract_duration,
+        "TargetCompletionDate" => &raw.target_completion_date,
+        _ => "",
+    };
+    Cow::Borrowed(value.trim())
+}
+
+//9 = digit, A = letter, ? = any character, * = any run of characters, anything else literally
+fn matches_pattern(value: &str, pattern: &[char]) -> bool {
+    match pattern.split_first() {
+        None => value.is_empty(),
+        Some(('*', rest)) => value
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(value.len()))
+            .any(|index| matches_pattern(&value[index..], rest)),
+        Some((expected, rest)) => {
+            let mut chars = value.chars();
+            let Some(c) = chars.next() else { return false };
+            let matched = match expected {
+                '9' => c.is_ascii_digit(),
+                'A' => c.is_alphabetic(),
+                '?' => true,
+                literal => c == *literal,
+            };
+            matched && matches_pattern(chars.as_str(), rest)
+        },
+    }
+}
+
+//the rules of the validation section, checked and with their patterns compiled before any row is read
+pub struct Rules<'a> {
+    config: &'a AnalysisConfig,
+    patterns: Vec<Vec<char>>, //per rule, in order; empty for rules other than Pattern
+}
+
+//rejects rules naming unknown columns or empty ranges before any row is read
+pub fn check_rules(config: &AnalysisConfig) -> Result<Rules<'_>, Box<dyn Error>> {
+    for rule in &config.validation.rules {
+        let columns: Vec<&String> = match rule {
+            Rule::Required { columns, .. } | Rule::Positive { columns, .. } => columns.iter().collect(),
+            Rule::Range { column, min, max, .. } => {
+                if min > max {
+                    return Err(format!("Validation rule for {} has min {} above max {} in data/{}", column, min, max, CONFIG_FILENAME).into());
+                }
+                vec![column]
+            },
+            Rule::Date { column, .. } | Rule::Pattern { column, .. } => vec![column],
+            Rule::Reference { max_capital_km, .. } if *max_capital_km < 0.0 => {
+                return Err(format!("Validation rule reference has a negative max_capital_km in data/{}", CONFIG_FILENAME).into());
+            },
+            Rule::CostWithinBudget { .. } | Rule::RegionProvince { .. } | Rule::Reference { .. } => Vec::new(),
+        };
+        if let Some(unknown) = columns.iter().find(|column| !schema::is_known_column(column)) {
+            return Err(format!("Unknown column {} in validation.rules of data/{}", unknown, CONFIG_FILENAME).into());
+        }
+    }
+
+    let patterns = config.validation.rules.iter().map(|rule| match rule {
+        Rule::Pattern { pattern, .. } => pattern.chars().collect(),
+        _ => Vec::new(),
+    }).collect();
+    Ok(Rules { config, patterns })
+}
+
+//every rule except region/province, which needs the rows in file order
+pub fn check_row(raw: &RawFloodControlProject, rules: &Rules, scorecard: &mut Scorecard) -> Vec<Severity> {
+    let mut issues: Vec<Severity> = Vec::new();
+    let amount = |value: &str| money::parse_money(value, rules.config.parsing.money_mode).amount();
+
+    for (rule, pattern) in rules.config.validation.rules.iter().zip(&rules.patterns) {
+        match rule {
+            Rule::Required { columns, severity } => {
+                for column in columns {
+                    let failed = raw_field(raw, column).is_empty().then_some(*severity);
+                    scorecard.check(column, failed, &mut issues);
+                }
+            },
+            Rule::Positive { columns, severity } => {
+                for column in columns {
+                    let value = raw_field(raw, column);
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let failed = (!amount(&value).is_some_and(|n| n > 0.0)).then_some(*severity);
+                    scorecard.check(column, failed, &mut issues);
+                }
+            },
+            Rule::Range { column, min, max, severity } => {
+                let value = raw_field(raw, column);
+                if value.is_empty() {
+                    continue;
+                }
+                let failed = (!amount(&value).is_some_and(|n| (*min..=*max).contains(&n))).then_some(*severity);
+                scorecard.check(column, failed, &mut issues);
+            },
+            Rule::Date { column, min_year, max_year, severity } => {
+                let value = raw_field(raw, column);
+                if value.is_empty() {
+                    continue;
+                }
+                let in_range = dates::parse_date(&value).is_some_and(|date| {
+                    min_year.is_none_or(|min| date.year() >= min) && max_year.is_none_or(|max| date.year() <= max)
+                });
+                scorecard.check(column, (!in_range).then_some(*severity), &mut issues);
+            },
+            Rule::CostWithinBudget { max_ratio, severity } => {
+                let (Some(budget), Some(cost)) = (amount(&raw.approved_budget_for_contract), amount(&raw.contract_cost)) else {
+                    continue; //left to the required and positive rules
+                };
+                let failed = (budget > 0.0 && cost > budget * max_ratio).then_some(*severity);
+                scorecard.check("ContractCost", failed, &mut issues);
+            },
+            Rule::RegionProvince { .. } => {},
+            Rule::Pattern { column, severity, .. } => {
+                let value = raw_field(raw, column);
+                if value.is_empty() {
+                    continue;
+                }
+                let failed = (!matches_pattern(&value, pattern)).then_some(*severity);
+                scorecard.check(column, failed, &mut issues);
+            },
+            Rule::Reference { max_capital_km, severity } => {
+                for check in geo::check(raw, *max_capital_km) {
+                    scorecard.check(check.column, check.mismatch.is_some().then_some(*severity), &mut issues);
+                    if let Some(mismatch) = check.mismatch {
+                        scorecard.reference.record(mismatch);
+                    }
+                }
+            },
+        }
+    }
+
+    issues
+}
+
+pub fn display_scorecard(scorecard: &Scorecard) {
+    println!("\nData Quality Scorecard ({} rows checked; Pass% / Clean% exclude warn and drop failures)", scorecard.rows());
+    println!("--------------------------------------------------------------------------------------------------");
+    println!("| {:<35} | {:>10} | {:>8} | {:>8} | {:>8} | {:>8} |", "Column", "Checks", "Info", "Warn", "Drop", "Pass%");
+    println!("--------------------------------------------------------------------------------------------------");
+    for (column, tally) in &scorecard.columns {
+        println!("| {:<35} | {:>10} | {:>8} | {:>8} | {:>8} | {:>8.2} |",
+            column, tally.checked, tally.info, tally.warn, tally.drop, tally.pass_pct());
+    }
+    println!("--------------------------------------------------------------------------------------------------");
+    println!("| {:<35} | {:>10} | {:>8} | {:>8} | {:>8} | {:>8} |", "Region", "Rows", "Info", "Warn", "Drop", "Clean%");
+    println!("--------------------------------------------------------------------------------------------------");
+    for (region, tally) in &scorecard.regions {
+        let region: String = region.chars().take(35).collect();
+        println!("| {:<35} | {:>10} | {:>8} | {:>8} | {:>8} | {:>8.2} |",
+            region, tally.checked, tally.info, tally.warn, tally.drop, tally.pass_pct());
+    }
+    println!("--------------------------------------------------------------------------------------------------");
+}
+
+pub fn export_scorecard_csv(scorecard: &Scorecard, filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(data_path(filename))?;
+
+    //header
+    writer.write_record(["GroupBy", "Group", "Checked", "Info", "Warn", "Drop", "Pass%"])?;
+
+    //format and write data
+    let columns = scorecard.columns.iter().map(|(column, tally)| ("Column", column.as_str(), tally));
+    let regions = scorecard.regions.iter().map(|(region, tally)| ("Region", region.as_ref(), tally));
+    for (group_by, group, tally) in columns.chain(regions) {
+        writer.write_record([
+            group_by,
+            group,
+            &tally.checked.to_string(),
+            &tally.info.to_string(),
+            &tally.warn.to_string(),
+            &tally.drop.to_string(),
+            &format!("{:.2}", tally.pass_pct()),
+        ])?;
+    }
+
+    writer.flush()?;
+    println!("✓ Exported to data/{}", filename);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(rules: Vec<Rule>) -> AnalysisConfig {
+        AnalysisConfig { validation: ValidationConfig { rules }, ..AnalysisConfig::default() }
+    }
+
+    fn check(rules: Vec<Rule>, fields: &[(&str, &str)]) -> Vec<Severity> {
+        let config = config(rules);
+        check_row(&RawFloodControlProject::sample(fields), &check_rules(&config).unwrap(), &mut Scorecard::default())
+    }
+
+    fn pattern(value: &str, pattern: &str) -> bool {
+        matches_pattern(value, &pattern.chars().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn matches_wildcard_patterns() {
+        assert!(pattern("P00123", "P*"));
+        assert!(pattern("P-12", "A-99"));
+        assert!(pattern("ab", "??"));
+        assert!(pattern("", "*"));
+        assert!(pattern("Ñ-1", "A-9"));
+        assert!(pattern("año 2", "a*9"));
+        assert!(!pattern("P-1A", "A-99"));
+        assert!(!pattern("C123", "P*"));
+        assert!(!pattern("P123x", "P999"));
+    }
+
+    #[test]
+    fn rejects_unknown_columns_and_empty_ranges() {
+        let unknown = Rule::Required { columns: vec!["Budgett".to_string()], severity: Severity::Warn };
+        let empty = Rule::Range { column: "ProjectLatitude".to_string(), min: 5.0, max: 4.0, severity: Severity::Warn };
+        assert!(check_rules(&config(vec![unknown])).is_err());
+        assert!(check_rules(&config(vec![empty])).is_err());
+        assert!(check_rules(&AnalysisConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn blank_values_fail_only_the_required_rule() {
+        let columns = vec!["ContractCost".to_string()];
+        let required = Rule::Required { columns: columns.clone(), severity: Severity::Drop };
+        let positive = Rule::Positive { columns, severity: Severity::Warn };
+        assert_eq!(check(vec![required, positive.clone()], &[("ContractCost", " ")]), vec![Severity::Drop]);
+        assert_eq!(check(vec![positive], &[("ContractCost", "-5")]), vec![Severity::Warn]);
+    }
+
+    #[test]
+    fn checks_ranges_dates_and_cost_ratio() {
+        let range = Rule::Range { column: "ProjectLatitude".to_string(), min: 4.5, max: 21.5, severity: Severity::Warn };
+        assert!(check(vec![range.clone()], &[("ProjectLatitude", "14.6")]).is_empty());
+        assert_eq!(check(vec![range], &[("ProjectLatitude", "40")]), vec![Severity::Warn]);
+
+        let date = Rule::Date { column: "StartDate".to_string(), min_year: Some(2015), max_year: None, severity: Severity::Drop };
+        assert_eq!(check(vec![date.clone()], &[("StartDate", "2014-12-31")]), vec![Severity::Drop]);
+        assert_eq!(check(vec![date], &[("StartDate", "someday")]), vec![Severity::Drop]);
+
+        let ratio = Rule::CostWithinBudget { max_ratio: 1.25, severity: Severity::Info };
+        assert!(check(vec![ratio.clone()], &[("ContractCost", "1250000")]).is_empty());
+        assert_eq!(check(vec![ratio], &[("ContractCost", "1250001")]), vec![Severity::Info]);
+    }
+
+    #[test]
+    fn drop_issues_leave_the_row_out() {
+        let project = Some(FloodControlProject::sample("P1", "Region I", "Builder A"));
+        let row = |issues: Vec<Severity>| ValidatedRow { region: "Region I".into(), province: "Province".into(), issues, project: project.clone() };
+        let mut scorecard = Scorecard::default();
+
+        assert!(scorecard.accept(row(vec![Severity::Info, Severity::Warn])).is_some());
+        assert!(scorecard.accept(row(vec![Severity::Warn, Severity::Drop])).is_none());
+        assert!(scorecard.accept(row(Vec::new())).is_some());
+        assert_eq!((scorecard.rows(), scorecard.rows_with(Severity::Warn), scorecard.rows_with(Severity::Drop)), (3, 1, 1));
+        assert_eq!(scorecard.rows_with(Severity::Info), 0);
+    }
+
+    #[test]
+    fn provinces_keep_their_first_region() {
+        let rules = ValidationConfig { rules: vec![Rule::RegionProvince { severity: Severity::Warn }] };
+        let mut regions = ProvinceRegions::default();
+        let mut scorecard = Scorecard::default();
+        let mut check = |region: &str, province: &str| {
+            let mut row = ValidatedRow { region: region.into(), province: province.into(), issues: Vec::new(), project: None };
+            regions.check(&mut row, &rules, &mut scorecard);
+            row.issues
+        };
+
+        assert!(check("Region I", "Ilocos Norte").is_empty());
+        assert!(check("Region I", "Ilocos Norte").is_empty());
+        assert_eq!(check("Region II", "Ilocos Norte"), vec![Severity::Warn]);
+        assert!(check("Region II", "").is_empty());
+    }
+
+    #[test]
+    fn pass_rate_ignores_info_failures() {
+        let tally = Tally { checked: 4, info: 2, warn: 1, drop: 0 };
+        assert_eq!(tally.pass_pct(), 75.0);
+        assert_eq!(Tally::default().pass_pct(), 100.0);
+    }
+}